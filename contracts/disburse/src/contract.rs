@@ -24,7 +24,7 @@ pub fn instantiate(
 
     for payee in msg.payees.clone().into_iter() {
         let mut total_percent_paid = Decimal::zero();
-        total_percent_paid = total_percent_paid + payee.percent_paid;
+        total_percent_paid += payee.percent_paid;
 
         if total_percent_paid != Decimal::one() {
             return Err(ContractError::InvalidPercentPaid {})
@@ -53,7 +53,7 @@ pub fn execute(
     match msg {
         ExecuteMsg::UpdateConfig { admin, nft_switch_address } => execute_update_config(deps, env, info, admin, nft_switch_address),
         ExecuteMsg::UpdatePayees { payees } => execute_update_payees(deps, env, info, payees),
-        ExecuteMsg::DisburseReward { amount } => execute_update_disburse_reward(deps, env, info, amount),
+        ExecuteMsg::DisburseReward { amount, denom } => execute_update_disburse_reward(deps, env, info, amount, denom),
     
     }
 }
@@ -100,7 +100,7 @@ pub fn execute_update_payees(
 
     for payee in payees.clone().into_iter() {
         let mut total_percent_paid = Decimal::zero();
-        total_percent_paid = total_percent_paid + payee.percent_paid;
+        total_percent_paid += payee.percent_paid;
 
         if total_percent_paid != Decimal::one() {
             return Err(ContractError::InvalidPercentPaid {})
@@ -122,6 +122,7 @@ pub fn execute_update_disburse_reward(
     _env: Env,
     info: MessageInfo,
     amount: Uint128,
+    denom: String,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let mut res = Response::new();
@@ -139,7 +140,7 @@ pub fn execute_update_disburse_reward(
    
         res.messages.push(SubMsg::new(BankMsg::Send {
             to_address: payee.payee_address.to_string(),
-            amount: vec![coin(amount_disburse.u128(), "uluna")],
+            amount: vec![coin(amount_disburse.u128(), &denom)],
         }));
 
     }
@@ -147,7 +148,8 @@ pub fn execute_update_disburse_reward(
     Ok(res
         .add_attribute("action", "disburse_reward")
         .add_attribute("sender", info.sender)
-        .add_attribute("amount", amount))
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom))
 
 }
 
@@ -1,6 +1,5 @@
 use cosmwasm_std::{StdError, Timestamp};
 use cw_utils::PaymentError;
-use sg1::FeeError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -41,9 +40,6 @@ pub enum ContractError {
     #[error("Max minting limit per address exceeded")]
     MaxPerAddressLimitExceeded {},
 
-    #[error("{0}")]
-    Fee(#[from] FeeError),
-
     #[error("InvalidUnitPrice {0} < {1}")]
     InvalidUnitPrice(u128, u128),
 
@@ -21,7 +21,8 @@ pub enum ExecuteMsg {
         payees: Vec<Payees>
     },
     DisburseReward {
-        amount: Uint128
+        amount: Uint128,
+        denom: String,
     }
 }
 
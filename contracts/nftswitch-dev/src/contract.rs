@@ -1,14 +1,60 @@
 use crate::execute::{
-    try_cancel_trade, try_create_trade, try_execute_trade, try_update_config, try_confirm_trade,
+    try_accept_collection_offer, try_accept_offer, try_cancel_listing, try_cancel_trade, try_confirm_listing,
+    try_create_bundle, try_create_collection_offer, try_create_listing, try_create_offer, try_create_swap, try_create_trade,
+    try_accept_transfer, try_cancel_transfer, try_grant_role, try_revoke_role, try_set_pause, try_propose_transfer, try_renounce_admin, AdminRole,
+    try_apply_collection_fee_change, try_apply_fee_change, try_cancel_collection_fee_change,
+    try_cancel_fee_change, try_set_fee_bounds,
+    try_execute_listing, try_execute_trade, try_receive, try_update_config, try_confirm_trade,
+    try_prune_expired_trades, try_register_operator, try_remove_collection_fees,
+    try_remove_holder_discount, try_revoke_operator, try_set_collection_fees,
+    try_set_holder_discount, try_set_royalty_policy,
+    try_withdraw_collection_offer, try_withdraw_offer,
+};
+use crate::msg::QueryMsg::{
+    GetCollectionFees, GetCollectionOffer, GetCollectionOffersByBidder, GetCollectionOffersByCollection,
+    GetConfig, GetContractVersion, GetHolderDiscount, GetListing, GetListingsByCollection, GetListingsBySeller, GetOffer,
+    GetOffersByBidder, GetOffersByCollection, GetOffersByToken, GetOperator, GetOperators,
+    GetAllTrades, GetBuyerStats, GetCollectionStats, GetGlobalStats, GetRoyaltyPolicy,
+    GetSellerStats, GetSale, GetSalesByBuyer, GetSalesByCollection,
+    GetSalesBySeller, GetSalesByToken, GetTokenReservation, GetTrade, GetTradesByBuyer,
+    GetTradesByCollection, GetTradesBySeller, GetPauseMatrix, GetRoleMembers, HasRole,
+    GetFeeBounds, GetPendingCollectionFeeChange, GetPendingFeeChange,
 };
-use crate::msg::QueryMsg::{GetConfig, GetTrade, GetTradesByBuyer, GetTradesBySeller};
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::query::{query_config, query_trade, query_trades_by_seller, query_trades_by_buyer};
-use crate::state::{Config, ExecuteEnv, CONFIG};
+use crate::helpers::{cw20_listing_fees_validate, fee_caps_validate, listing_fees_validate, pubkey_validate};
+use crate::query::{
+    query_collection_fees, query_collection_offer, query_holder_discount, query_collection_offers_by_bidder,
+    query_collection_offers_by_collection, query_config, query_contract_version, query_listing, query_listings_by_collection, query_listings_by_seller,
+    query_offer, query_offers_by_bidder, query_offers_by_collection, query_offers_by_token,
+    query_fee_bounds, query_pending_collection_fee_change, query_pending_fee_change,
+    query_operator, query_operators, query_has_role, query_pause_matrix, query_role_members, query_royalty_policy, query_token_reservation,
+    query_all_trades, query_buyer_stats, query_collection_stats, query_global_stats,
+    query_seller_stats, query_sale, query_sales_by_buyer, query_sales_by_collection,
+    query_sales_by_seller, query_sales_by_token, query_trade, query_trades_by_buyer, query_trades_by_collection,
+    query_trades_by_seller,
+};
+use crate::state::{
+    trades, Config, ExecuteEnv, FeeBounds, Pauses, Trade, CONFIG, LEGACY_CONFIG, LEGACY_TRADES,
+    TOKEN_RESERVATIONS,
+};
 use crate::ContractError;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    coin, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+};
+use cw2::{set_contract_version, CONTRACT};
+use semver::Version;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:nftswitch";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// first release tracking its version with cw2, older deployments have no version stored
+const FIRST_VERSIONED_RELEASE: Version = Version::new(1, 1, 0);
+
+// 1.0.0 charged its single listing fee in this denom
+const LEGACY_LISTING_FEE_DENOM: &str = "uluna";
 
 #[cfg_attr(feature = "library", entry_point)]
 pub fn instantiate(
@@ -17,17 +63,30 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    listing_fees_validate(&msg.listing_fees)?;
+    let cw20_listing_fees = cw20_listing_fees_validate(deps.as_ref(), msg.cw20_listing_fees)?;
+    if let Some(pubkey) = &msg.fee_admin_pubkey {
+        pubkey_validate(pubkey)?;
+    }
+    fee_caps_validate(&FeeBounds::default(), Some(msg.buyer_fee), Some(msg.seller_fee))?;
+
     let config = Config {
         admin: info.sender.clone(),
         fee_admin: deps.api.addr_validate(&msg.fee_admin)?,
         commission_addr: deps.api.addr_validate(&msg.commission_addr)?,
         seller_fee: msg.seller_fee,
-        buyer_fee: msg.seller_fee,
-        listing_fee: msg.listing_fee,
+        buyer_fee: msg.buyer_fee,
+        listing_fees: msg.listing_fees,
+        cw20_listing_fees,
         e_break: false,
+        pauses: Pauses::default(),
+        fee_admin_pubkey: msg.fee_admin_pubkey,
+        pending_admin: None,
+        pending_fee_admin: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -35,8 +94,82 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let current = parse_version(CONTRACT_VERSION)?;
+
+    // deployments older than cw2 versioning are recognised by their config
+    let stored = match CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::InvalidContractVersion {});
+            }
+            parse_version(&stored.version)?
+        }
+        None => {
+            if deps.storage.get(CONFIG.as_slice()).is_none() {
+                return Err(ContractError::InvalidContractVersion {});
+            }
+            Version::new(1, 0, 0)
+        }
+    };
+
+    if stored > current {
+        return Err(ContractError::InvalidContractVersion {});
+    }
+
+    if stored < FIRST_VERSIONED_RELEASE {
+        migrate_from_unversioned(deps.branch())?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidContractVersion {})
+}
+
+// rewrite the 1.0.0 config and trades in the current schema and reserve the tokens of live trades
+fn migrate_from_unversioned(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+    let config = Config {
+        admin: legacy.admin,
+        fee_admin: legacy.fee_admin,
+        commission_addr: legacy.commission_addr,
+        seller_fee: legacy.seller_fee,
+        buyer_fee: legacy.buyer_fee,
+        listing_fees: vec![coin(legacy.listing_fee.u128(), LEGACY_LISTING_FEE_DENOM)],
+        cw20_listing_fees: vec![],
+        e_break: legacy.e_break,
+        pauses: Pauses::default(),
+        fee_admin_pubkey: None,
+        pending_admin: None,
+        pending_fee_admin: None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    let stored_trades = LEGACY_TRADES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (key, legacy) in stored_trades {
+        let trade = Trade::from(legacy);
+        // the old entry can not be read as a `Trade`, so it goes before the indexed save
+        LEGACY_TRADES.remove(deps.storage, key.clone());
+        for nft in trade.seller_nfts() {
+            let token = (nft.collection, nft.token_id);
+            if !TOKEN_RESERVATIONS.has(deps.storage, token.clone()) {
+                TOKEN_RESERVATIONS.save(deps.storage, token, &key)?;
+            }
+        }
+        trades().save(deps.storage, key, &trade)?;
+    }
+
+    Ok(())
 }
 
 #[cfg_attr(feature = "library", entry_point)]
@@ -52,47 +185,91 @@ pub fn execute(
             nft_id,
             buyer_addr,
             sale_price,
+            expires_at,
+            escrow_nft,
+            on_behalf_of,
         } => try_create_trade(
             ExecuteEnv { deps, env, info },
+            None,
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            expires_at,
+            escrow_nft.unwrap_or(false),
+            on_behalf_of,
+        ),
+        ExecuteMsg::CreateSwap {
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            offered_nfts,
+            wanted_nfts,
+            seller_coins,
+            on_behalf_of,
+        } => try_create_swap(
+            ExecuteEnv { deps, env, info },
+            None,
             nft_addr,
             nft_id,
             buyer_addr,
             sale_price,
+            offered_nfts,
+            wanted_nfts,
+            seller_coins,
+            on_behalf_of,
+        ),
+        ExecuteMsg::CreateBundle {
+            nfts,
+            buyer_addr,
+            sale_price,
+            on_behalf_of,
+        } => try_create_bundle(
+            ExecuteEnv { deps, env, info },
+            None,
+            nfts,
+            buyer_addr,
+            sale_price,
+            on_behalf_of,
         ),
         ExecuteMsg::CancelTrade {
             buyer,
             seller,
             nft_collection,
             nft_id,
-        } => try_cancel_trade(deps, info, buyer, seller, nft_collection, nft_id),
+        } => try_cancel_trade(deps, env, info, buyer, seller, nft_collection, nft_id),
         ExecuteMsg::ExecuteTrade {
             buyer,
             nft_collection,
             nft_id,
+            fee_confirmation,
         } => try_execute_trade(
             ExecuteEnv { deps, env, info },
+            None,
             buyer,
             nft_collection,
             nft_id,
+            fee_confirmation,
         ),
         ExecuteMsg::UpdateConfig {
-            admin,
-            fee_admin,
             commission_addr,
             e_break,
             buyer_fee,
             seller_fee,
-            listing_fee
+            listing_fees,
+            cw20_listing_fees,
+            fee_admin_pubkey,
         } => try_update_config(
             deps,
             env,
             info,
-            admin,
-            fee_admin,
             commission_addr,
             buyer_fee,
             seller_fee,
-            listing_fee,
+            listing_fees,
+            cw20_listing_fees,
+            fee_admin_pubkey,
             e_break,
         ),
         ExecuteMsg::ConfirmTrade {
@@ -111,11 +288,167 @@ pub fn execute(
             buyer_fee_pct,
             is_confirmed_by_fee_admin,
         ),
+        ExecuteMsg::Receive(msg) => try_receive(ExecuteEnv { deps, env, info }, msg),
+        ExecuteMsg::CreateListing {
+            nft_addr,
+            nft_id,
+            sale_price,
+        } => try_create_listing(
+            ExecuteEnv { deps, env, info },
+            None,
+            nft_addr,
+            nft_id,
+            sale_price,
+        ),
+        ExecuteMsg::CancelListing {
+            nft_collection,
+            nft_id,
+        } => try_cancel_listing(deps, info, nft_collection, nft_id),
+        ExecuteMsg::ConfirmListing {
+            nft_collection,
+            nft_id,
+            seller_fee_pct,
+            buyer_fee_pct,
+            is_confirmed_by_fee_admin,
+        } => try_confirm_listing(
+            ExecuteEnv { deps, env, info },
+            nft_collection,
+            nft_id,
+            seller_fee_pct,
+            buyer_fee_pct,
+            is_confirmed_by_fee_admin,
+        ),
+        ExecuteMsg::ExecuteListing {
+            nft_collection,
+            nft_id,
+        } => try_execute_listing(
+            ExecuteEnv { deps, env, info },
+            None,
+            nft_collection,
+            nft_id,
+        ),
+        ExecuteMsg::CreateOffer {
+            nft_collection,
+            nft_id,
+            price,
+        } => try_create_offer(
+            ExecuteEnv { deps, env, info },
+            None,
+            nft_collection,
+            nft_id,
+            price,
+        ),
+        ExecuteMsg::WithdrawOffer {
+            nft_collection,
+            nft_id,
+        } => try_withdraw_offer(deps, info, nft_collection, nft_id),
+        ExecuteMsg::AcceptOffer {
+            nft_collection,
+            nft_id,
+            bidder,
+        } => try_accept_offer(
+            ExecuteEnv { deps, env, info },
+            nft_collection,
+            nft_id,
+            bidder,
+        ),
+        ExecuteMsg::CreateCollectionOffer {
+            nft_collection,
+            price,
+            quantity,
+        } => try_create_collection_offer(
+            ExecuteEnv { deps, env, info },
+            None,
+            nft_collection,
+            price,
+            quantity,
+        ),
+        ExecuteMsg::WithdrawCollectionOffer { nft_collection } => {
+            try_withdraw_collection_offer(deps, info, nft_collection)
+        }
+        ExecuteMsg::AcceptCollectionOffer {
+            nft_collection,
+            nft_id,
+            bidder,
+        } => try_accept_collection_offer(
+            ExecuteEnv { deps, env, info },
+            nft_collection,
+            nft_id,
+            bidder,
+        ),
+        ExecuteMsg::PruneExpiredTrades { limit, start_after } => {
+            try_prune_expired_trades(deps, env, info, limit, start_after)
+        }
+        ExecuteMsg::RegisterOperator {
+            operator,
+            expires_at,
+            collections,
+        } => try_register_operator(deps, info, operator, expires_at, collections),
+        ExecuteMsg::RevokeOperator { operator } => try_revoke_operator(deps, info, operator),
+        ExecuteMsg::SetRoyaltyPolicy { collection, policy } => {
+            try_set_royalty_policy(deps, info, collection, policy)
+        }
+        ExecuteMsg::SetCollectionFees {
+            collection,
+            buyer_fee,
+            seller_fee,
+            partner,
+        } => try_set_collection_fees(deps, env, info, collection, buyer_fee, seller_fee, partner),
+        ExecuteMsg::RemoveCollectionFees { collection } => {
+            try_remove_collection_fees(deps, env, info, collection)
+        }
+        ExecuteMsg::SetHolderDiscount {
+            membership_collection,
+            buyer_discount,
+            seller_discount,
+        } => try_set_holder_discount(
+            deps,
+            info,
+            membership_collection,
+            buyer_discount,
+            seller_discount,
+        ),
+        ExecuteMsg::RemoveHolderDiscount {
+            membership_collection,
+        } => try_remove_holder_discount(deps, info, membership_collection),
+        ExecuteMsg::ProposeAdmin {
+            new_admin,
+            expires_at,
+        } => try_propose_transfer(deps, env, info, AdminRole::Admin, new_admin, expires_at),
+        ExecuteMsg::AcceptAdmin {} => try_accept_transfer(deps, env, info, AdminRole::Admin),
+        ExecuteMsg::CancelAdminProposal {} => try_cancel_transfer(deps, info, AdminRole::Admin),
+        ExecuteMsg::RenounceAdmin {} => try_renounce_admin(deps, env, info),
+        ExecuteMsg::ProposeFeeAdmin {
+            new_fee_admin,
+            expires_at,
+        } => try_propose_transfer(deps, env, info, AdminRole::FeeAdmin, new_fee_admin, expires_at),
+        ExecuteMsg::AcceptFeeAdmin {} => try_accept_transfer(deps, env, info, AdminRole::FeeAdmin),
+        ExecuteMsg::CancelFeeAdminProposal {} => try_cancel_transfer(deps, info, AdminRole::FeeAdmin),
+        ExecuteMsg::GrantRole { role, address } => try_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => try_revoke_role(deps, info, role, address),
+        ExecuteMsg::SetPause {
+            action,
+            paused,
+            collection,
+        } => try_set_pause(deps, info, action, paused, collection),
+        ExecuteMsg::SetFeeBounds {
+            max_buyer_fee,
+            max_seller_fee,
+            increase_delay,
+        } => try_set_fee_bounds(deps, info, max_buyer_fee, max_seller_fee, increase_delay),
+        ExecuteMsg::ApplyFeeChange {} => try_apply_fee_change(deps, env, info),
+        ExecuteMsg::CancelFeeChange {} => try_cancel_fee_change(deps, info),
+        ExecuteMsg::ApplyCollectionFeeChange { collection } => {
+            try_apply_collection_fee_change(deps, env, info, collection)
+        }
+        ExecuteMsg::CancelCollectionFeeChange { collection } => {
+            try_cancel_collection_fee_change(deps, info, collection)
+        }
     }
 }
 
 #[cfg_attr(feature = "library", entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let api = deps.api;
 
     match msg {
@@ -130,10 +463,207 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             api.addr_validate(&nft_collection)?,
             nft_id,
         )?),
-        // GetAllTrades {} => to_binary(&query_trades(deps)?),
-        // GetTrades {} => to_binary(&query_trades(deps)?),
-        GetTradesByBuyer { buyer, limit } => to_binary(&query_trades_by_buyer(deps, buyer, limit)?),
-        GetTradesBySeller { seller, limit } => { to_binary(&query_trades_by_seller(deps, seller, limit)?) }
+        GetTradesByBuyer {
+            buyer,
+            limit,
+            include_expired,
+            start_after,
+        } => to_binary(&query_trades_by_buyer(
+            deps,
+            env,
+            buyer,
+            limit,
+            include_expired,
+            start_after,
+        )?),
+        GetTradesBySeller {
+            seller,
+            limit,
+            include_expired,
+            start_after,
+        } => to_binary(&query_trades_by_seller(
+            deps,
+            env,
+            seller,
+            limit,
+            include_expired,
+            start_after,
+        )?),
+        GetTradesByCollection {
+            collection,
+            limit,
+            include_expired,
+            start_after,
+            filter,
+        } => to_binary(&query_trades_by_collection(
+            deps,
+            env,
+            collection,
+            limit,
+            include_expired,
+            start_after,
+            filter,
+        )?),
+        GetAllTrades {
+            limit,
+            include_expired,
+            start_after,
+            filter,
+        } => to_binary(&query_all_trades(deps, env, limit, include_expired, start_after, filter)?),
+        GetTokenReservation {
+            nft_collection,
+            nft_id,
+        } => to_binary(&query_token_reservation(
+            deps,
+            api.addr_validate(&nft_collection)?,
+            nft_id,
+        )?),
+        GetListing {
+            nft_collection,
+            nft_id,
+        } => to_binary(&query_listing(deps, api.addr_validate(&nft_collection)?, nft_id)?),
+        GetListingsByCollection {
+            collection,
+            limit,
+            start_after,
+        } => to_binary(&query_listings_by_collection(deps, collection, limit, start_after)?),
+        GetListingsBySeller {
+            seller,
+            limit,
+            start_after,
+        } => to_binary(&query_listings_by_seller(deps, seller, limit, start_after)?),
+        GetOffer {
+            nft_collection,
+            nft_id,
+            bidder,
+        } => to_binary(&query_offer(
+            deps,
+            api.addr_validate(&nft_collection)?,
+            nft_id,
+            api.addr_validate(&bidder)?,
+        )?),
+        GetOffersByToken {
+            nft_collection,
+            nft_id,
+            limit,
+            start_after,
+        } => to_binary(&query_offers_by_token(
+            deps,
+            api.addr_validate(&nft_collection)?,
+            nft_id,
+            limit,
+            start_after,
+        )?),
+        GetOffersByBidder {
+            bidder,
+            limit,
+            start_after,
+        } => to_binary(&query_offers_by_bidder(deps, bidder, limit, start_after)?),
+        GetOffersByCollection {
+            collection,
+            limit,
+            start_after,
+        } => to_binary(&query_offers_by_collection(deps, collection, limit, start_after)?),
+        GetCollectionOffer {
+            nft_collection,
+            bidder,
+        } => to_binary(&query_collection_offer(
+            deps,
+            api.addr_validate(&nft_collection)?,
+            api.addr_validate(&bidder)?,
+        )?),
+        GetCollectionOffersByCollection {
+            collection,
+            limit,
+            start_after,
+        } => to_binary(&query_collection_offers_by_collection(
+            deps,
+            collection,
+            limit,
+            start_after,
+        )?),
+        GetCollectionOffersByBidder {
+            bidder,
+            limit,
+            start_after,
+        } => to_binary(&query_collection_offers_by_bidder(deps, bidder, limit, start_after)?),
+        GetOperator {
+            principal,
+            operator,
+        } => to_binary(&query_operator(
+            deps,
+            api.addr_validate(&principal)?,
+            api.addr_validate(&operator)?,
+        )?),
+        GetOperators {
+            principal,
+            limit,
+            start_after,
+        } => to_binary(&query_operators(deps, principal, limit, start_after)?),
+        GetRoyaltyPolicy { collection } => {
+            to_binary(&query_royalty_policy(deps, api.addr_validate(&collection)?)?)
+        }
+        GetCollectionFees { collection } => {
+            to_binary(&query_collection_fees(deps, api.addr_validate(&collection)?)?)
+        }
+        GetContractVersion {} => to_binary(&query_contract_version(deps)?),
+        GetSale { id } => to_binary(&query_sale(deps, id)?),
+        GetSalesByCollection {
+            collection,
+            limit,
+            start_after,
+        } => to_binary(&query_sales_by_collection(deps, collection, limit, start_after)?),
+        GetSalesByToken {
+            nft_collection,
+            nft_id,
+            limit,
+            start_after,
+        } => to_binary(&query_sales_by_token(
+            deps,
+            api.addr_validate(&nft_collection)?,
+            nft_id,
+            limit,
+            start_after,
+        )?),
+        GetSalesByBuyer {
+            buyer,
+            limit,
+            start_after,
+        } => to_binary(&query_sales_by_buyer(deps, buyer, limit, start_after)?),
+        GetSalesBySeller {
+            seller,
+            limit,
+            start_after,
+        } => to_binary(&query_sales_by_seller(deps, seller, limit, start_after)?),
+        GetGlobalStats {} => to_binary(&query_global_stats(deps)?),
+        GetCollectionStats { collection } => {
+            to_binary(&query_collection_stats(deps, api.addr_validate(&collection)?)?)
+        }
+        GetSellerStats { seller } => {
+            to_binary(&query_seller_stats(deps, api.addr_validate(&seller)?)?)
+        }
+        GetBuyerStats { buyer } => {
+            to_binary(&query_buyer_stats(deps, api.addr_validate(&buyer)?)?)
+        }
+        GetHolderDiscount { address } => {
+            to_binary(&query_holder_discount(deps, api.addr_validate(&address)?)?)
+        }
+        GetRoleMembers {
+            role,
+            limit,
+            start_after,
+        } => to_binary(&query_role_members(deps, role, limit, start_after)?),
+        GetFeeBounds {} => to_binary(&query_fee_bounds(deps)?),
+        GetPendingFeeChange {} => to_binary(&query_pending_fee_change(deps)?),
+        GetPendingCollectionFeeChange { collection } => to_binary(
+            &query_pending_collection_fee_change(deps, api.addr_validate(&collection)?)?,
+        ),
+        GetPauseMatrix { limit, start_after } => {
+            to_binary(&query_pause_matrix(deps, limit, start_after)?)
+        }
+        HasRole { role, address } => {
+            to_binary(&query_has_role(deps, role, api.addr_validate(&address)?)?)
+        }
     }
 }
 
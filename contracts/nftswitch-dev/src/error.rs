@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Timestamp};
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -28,6 +28,48 @@ pub enum ContractError {
     #[error("Trade not confirmed")]
     TradeNotConfirmed {},
 
+    #[error("Trade expired")]
+    TradeExpired {},
+
+    #[error("No fee admin public key registered")]
+    MissingFeeAdminPubkey {},
+
+    #[error("Invalid secp256k1 public key")]
+    InvalidPubkey {},
+
+    #[error("Invalid fee confirmation signature")]
+    InvalidFeeSignature {},
+
+    #[error("Fee confirmation expired")]
+    FeeConfirmationExpired {},
+
+    #[error("Fee confirmation nonce already used")]
+    NonceAlreadyUsed {},
+
+    #[error("Collection enforces royalties but does not report them")]
+    RoyaltyUnavailable {},
+
+    #[error("Royalty exceeds the seller's proceeds")]
+    InvalidRoyalty {},
+
+    #[error("Partnership fees can not exceed the global fees")]
+    InvalidPartnerFees {},
+
+    #[error("Discount must be between 0 and 1")]
+    InvalidDiscount {},
+
+    #[error("At most {max} membership collections can grant discounts")]
+    TooManyHolderDiscounts { max: u32 },
+
+    #[error("Listing not confirmed")]
+    ListingNotConfirmed {},
+
+    #[error("Offer already made by this bidder")]
+    OfferAlreadyExists {},
+
+    #[error("Offer quantity must be greater than zero")]
+    InvalidQuantity {},
+
     #[error("Fees already confirmed")]
     AlreadyConfirmedFees {},
 
@@ -37,9 +79,30 @@ pub enum ContractError {
     #[error("Have to send listing fee to contract")]
     MissingListingFee {},
 
-    #[error("Emergency Break Activated -- All Actions Paused")]
+    #[error("Emergency Break Activated -- Trading Paused")]
     EmergencyBreakActivated {},
 
+    #[error("Buyer fee {fee} is above the {max} cap")]
+    BuyerFeeAboveCap { fee: Decimal, max: Decimal },
+
+    #[error("Seller fee {fee} is above the {max} cap")]
+    SellerFeeAboveCap { fee: Decimal, max: Decimal },
+
+    #[error("Fee caps must stay below 100% and at or above the global and collection fees")]
+    InvalidFeeCap {},
+
+    #[error("No fee change pending")]
+    NoPendingFeeChange {},
+
+    #[error("Fee change is locked until {effective_at}")]
+    FeeChangeLocked { effective_at: Timestamp },
+
+    #[error("{action} is paused")]
+    ActionPaused { action: String },
+
+    #[error("{action} is paused for collection {collection}")]
+    CollectionPaused { collection: String, action: String },
+
     #[error("Seller or Buyer parameter required.")]
     ParameterMissing {},
 
@@ -64,6 +127,21 @@ pub enum ContractError {
     #[error("{0}")]
     TradePaymentError(#[from] PaymentError),
 
+    #[error("Same NFT listed more than once")]
+    DuplicateNft {},
+
+    #[error("Swap must exchange at least one NFT on each side")]
+    InvalidSwap {},
+
+    #[error("Bundle must hold at least one NFT")]
+    EmptyBundle {},
+
+    #[error("No transfer pending for this role")]
+    NoPendingTransfer {},
+
+    #[error("Pending transfer has expired")]
+    TransferExpired {},
+
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
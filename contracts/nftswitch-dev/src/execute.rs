@@ -1,58 +1,102 @@
 
 use cosmwasm_std::{
-    coin, to_binary, BankMsg, Coin, Decimal, DepsMut, Env, Event, MessageInfo, Response,
-    StdResult, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use cw721::Cw721ExecuteMsg;
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
 use cw721_base::helpers::Cw721Contract;
-use disburse::msg::DisburseReward;
-use crate::helpers::{only_owner, price_validate};
-use crate::state::{trade_key, trades, Config, ExecuteEnv, Trade, CONFIG};
+use disburse::msg::ExecuteMsg as DisburseExecuteMsg;
+use crate::helpers::{
+    check_nft_approval, collection_fees, ensure_not_paused, fee_caps_validate, has_role, only_role, holder_discount, cw20_listing_fees_validate, listing_fee_for, listing_fees_validate,
+    must_pay_price, nfts_validate, only_owner, only_principal_or_operator, price_validate,
+    pubkey_validate, query_royalty,
+    transfer_nft_msg, transfer_price_msg, verify_fee_confirmation,
+};
+use crate::msg::{ReceiveMsg, SignedFeeConfirmation, TradeOffset};
+use crate::query::trade_offset_bound;
+use crate::state::{
+    collection_offer_key, collection_offers, listing_key, listings, offer_key, offers, sales,
+    trade_key, trades, CollectionOffer, Config, ExecuteEnv, Listing, NftItem, Offer, Sale, Trade,
+    TradePrice, TradeStats, BUYER_STATS, COLLECTION_STATS, GLOBAL_STATS, SELLER_STATS,
+    FeeBounds, FeeDiscount, FeeSchedule, Operator, PauseAction, PendingCollectionFeeChange, PendingFeeChange, FEE_BOUNDS, PENDING_COLLECTION_FEE_CHANGES, PENDING_FEE_CHANGE, PendingTransfer, Role, RoyaltyPolicy, COLLECTION_PAUSES, ROLES, TradeKey, COLLECTION_FEES, CONFIG,
+    FEE_CONFIRMATION_NONCES, HOLDER_DISCOUNTS, MAX_HOLDER_DISCOUNTS, OPERATORS, ROYALTY_POLICIES, SALE_COUNT,
+    TOKEN_RESERVATIONS,
+};
 use crate::ContractError;
-use cw_utils::{must_pay, nonpayable};
+use cw_utils::{nonpayable, Expiration};
 
-const NATIVE_DENOM: &str = "uluna";
+// Prune limits
+const DEFAULT_PRUNE_LIMIT: u32 = 10;
+const MAX_PRUNE_LIMIT: u32 = 30;
 
+#[allow(clippy::too_many_arguments)]
 pub fn try_update_config(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    admin: Option<String>,
-    fee_admin: Option<String>,
     commission_addr: Option<String>,
     buyer_fee: Option<Decimal>,
     seller_fee: Option<Decimal>,
-    listing_fee: Option<Uint128>,
+    listing_fees: Option<Vec<Coin>>,
+    cw20_listing_fees: Option<Vec<Cw20Coin>>,
+    fee_admin_pubkey: Option<Binary>,
     e_break: Option<bool>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
-    if info.sender != config.admin {
+    // fees and the emergency break may be delegated, everything else stays with the admin
+    let sets_fees =
+        buyer_fee.is_some() || seller_fee.is_some() || listing_fees.is_some() || cw20_listing_fees.is_some();
+    let sets_admin_fields = commission_addr.is_some() || fee_admin_pubkey.is_some();
+    let admin_only = sets_admin_fields || !(sets_fees || e_break.is_some());
+    if admin_only && info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
-
-    if let Some(admin) = admin {
-        config.admin = deps.api.addr_validate(&admin)?;
+    if sets_fees {
+        only_role(deps.as_ref(), &config, Role::FeeSetter, &info.sender)?;
     }
-
-    if let Some(fee_admin) = fee_admin {
-        config.fee_admin = deps.api.addr_validate(&fee_admin)?;
+    if e_break.is_some() {
+        only_role(deps.as_ref(), &config, Role::Pauser, &info.sender)?;
     }
 
     if let Some(commission_addr) = commission_addr {
         config.commission_addr = deps.api.addr_validate(&commission_addr)?;
     }
 
-    if let Some(buyer_fee) = buyer_fee {
-        config.buyer_fee = buyer_fee;
+    let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+    fee_caps_validate(&bounds, buyer_fee, seller_fee)?;
+
+    // fee cuts apply at once, increases wait out the timelock
+    let mut increase = PendingFeeChange {
+        buyer_fee: None,
+        seller_fee: None,
+        effective_at: env.block.time.plus_seconds(bounds.increase_delay),
+    };
+
+    match buyer_fee {
+        Some(fee) if fee > config.buyer_fee => increase.buyer_fee = Some(fee),
+        Some(fee) => config.buyer_fee = fee,
+        None => {}
+    }
+
+    match seller_fee {
+        Some(fee) if fee > config.seller_fee => increase.seller_fee = Some(fee),
+        Some(fee) => config.seller_fee = fee,
+        None => {}
+    }
+
+    if let Some(listing_fees) = listing_fees {
+        listing_fees_validate(&listing_fees)?;
+        config.listing_fees = listing_fees;
     }
 
-    if let Some(seller_fee) = seller_fee {
-        config.seller_fee = seller_fee;
+    if let Some(cw20_listing_fees) = cw20_listing_fees {
+        config.cw20_listing_fees = cw20_listing_fees_validate(deps.as_ref(), cw20_listing_fees)?;
     }
 
-    if let Some(listing_fee) = listing_fee {
-        config.listing_fee = Uint128::from(listing_fee);
+    if let Some(fee_admin_pubkey) = fee_admin_pubkey {
+        pubkey_validate(&fee_admin_pubkey)?;
+        config.fee_admin_pubkey = Some(fee_admin_pubkey);
     }
 
     if let Some(e_break) = e_break {
@@ -61,15 +105,273 @@ pub fn try_update_config(
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_attribute("action", "update_config"))
+    let mut res = Response::new().add_attribute("action", "update_config");
+    if increase.buyer_fee.is_some() || increase.seller_fee.is_some() {
+        PENDING_FEE_CHANGE.save(deps.storage, &increase)?;
+        res = res.add_attribute("fee_change_effective_at", increase.effective_at.to_string());
+    }
+
+    Ok(res)
+}
+
+pub fn try_receive(env: ExecuteEnv, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let ExecuteEnv { deps, info, env } = env;
+
+    // the CW20 contract calls us, acting on behalf of the token sender
+    let cw20_payment = Cw20CoinVerified {
+        address: info.sender,
+        amount: cw20_msg.amount,
+    };
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&cw20_msg.sender)?,
+        funds: vec![],
+    };
+
+    match from_binary(&cw20_msg.msg)? {
+        ReceiveMsg::CreateTrade {
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            expires_at,
+            escrow_nft,
+            on_behalf_of,
+        } => try_create_trade(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            expires_at,
+            escrow_nft.unwrap_or(false),
+            on_behalf_of,
+        ),
+        ReceiveMsg::CreateSwap {
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            offered_nfts,
+            wanted_nfts,
+            seller_coins,
+            on_behalf_of,
+        } => try_create_swap(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_addr,
+            nft_id,
+            buyer_addr,
+            sale_price,
+            offered_nfts,
+            wanted_nfts,
+            seller_coins,
+            on_behalf_of,
+        ),
+        ReceiveMsg::CreateBundle {
+            nfts,
+            buyer_addr,
+            sale_price,
+            on_behalf_of,
+        } => try_create_bundle(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nfts,
+            buyer_addr,
+            sale_price,
+            on_behalf_of,
+        ),
+        ReceiveMsg::ExecuteTrade {
+            buyer,
+            nft_collection,
+            nft_id,
+            fee_confirmation,
+        } => try_execute_trade(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            buyer,
+            nft_collection,
+            nft_id,
+            fee_confirmation,
+        ),
+        ReceiveMsg::CreateListing {
+            nft_addr,
+            nft_id,
+            sale_price,
+        } => try_create_listing(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_addr,
+            nft_id,
+            sale_price,
+        ),
+        ReceiveMsg::ExecuteListing {
+            nft_collection,
+            nft_id,
+        } => try_execute_listing(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_collection,
+            nft_id,
+        ),
+        ReceiveMsg::CreateOffer {
+            nft_collection,
+            nft_id,
+            price,
+        } => try_create_offer(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_collection,
+            nft_id,
+            price,
+        ),
+        ReceiveMsg::CreateCollectionOffer {
+            nft_collection,
+            price,
+            quantity,
+        } => try_create_collection_offer(
+            ExecuteEnv { deps, env, info },
+            Some(cw20_payment),
+            nft_collection,
+            price,
+            quantity,
+        ),
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn try_create_trade(
     env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_addr: String,
+    nft_id: String,
+    buyer_addr: String,
+    price: TradePrice,
+    expires_at: Option<Expiration>,
+    escrow_nft: bool,
+    on_behalf_of: Option<String>,
+) -> Result<Response, ContractError> {
+    create_trade(
+        env,
+        cw20_payment,
+        nft_addr,
+        nft_id,
+        buyer_addr,
+        price,
+        vec![],
+        vec![],
+        Uint128::zero(),
+        expires_at.unwrap_or_default(),
+        escrow_nft,
+        on_behalf_of,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_create_swap(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_addr: String,
+    nft_id: String,
+    buyer_addr: String,
+    price: TradePrice,
+    offered_nfts: Vec<NftItem>,
+    wanted_nfts: Vec<NftItem>,
+    seller_coins: Uint128,
+    on_behalf_of: Option<String>,
+) -> Result<Response, ContractError> {
+    if wanted_nfts.is_empty() {
+        return Err(ContractError::InvalidSwap {});
+    }
+
+    create_trade(
+        env,
+        cw20_payment,
+        nft_addr,
+        nft_id,
+        buyer_addr,
+        price,
+        offered_nfts,
+        wanted_nfts,
+        seller_coins,
+        Expiration::Never {},
+        false,
+        on_behalf_of,
+    )
+}
+
+pub fn try_create_bundle(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nfts: Vec<NftItem>,
+    buyer_addr: String,
+    price: TradePrice,
+    on_behalf_of: Option<String>,
+) -> Result<Response, ContractError> {
+    // the first NFT keys the trade, the others travel with it
+    let mut nfts = nfts.into_iter();
+    let first = nfts.next().ok_or(ContractError::EmptyBundle {})?;
+
+    create_trade(
+        env,
+        cw20_payment,
+        first.collection.to_string(),
+        first.token_id,
+        buyer_addr,
+        price,
+        nfts.collect(),
+        vec![],
+        Uint128::zero(),
+        Expiration::Never {},
+        false,
+        on_behalf_of,
+    )
+}
+
+// the listing fee is charged in the denom the trade is priced in
+fn collect_listing_fee(
+    cfg: &Config,
+    info: &MessageInfo,
+    cw20_payment: &Option<Cw20CoinVerified>,
+    price: &TradePrice,
+    escrowed: Uint128,
+) -> Result<TradePrice, ContractError> {
+    price_validate(price, cfg)?;
+
+    let listing_fee = listing_fee_for(cfg, price)?;
+
+    let expected_sent_amount = listing_fee.amount() + escrowed;
+
+    if expected_sent_amount.is_zero() {
+        nonpayable(info)?;
+        if cw20_payment.is_some() {
+            return Err(ContractError::MissingListingFee {});
+        }
+    } else {
+        let amount_send = must_pay_price(info, cw20_payment, &listing_fee)?;
+
+        if expected_sent_amount != amount_send {
+            return Err(ContractError::MissingListingFee {});
+        }
+    }
+
+    Ok(listing_fee)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_trade(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
     nft_addr: String,
     nft_id: String,
     buyer_addr: String,
-    price: Coin,
+    price: TradePrice,
+    offered_nfts: Vec<NftItem>,
+    wanted_nfts: Vec<NftItem>,
+    seller_coins: Uint128,
+    expires_at: Expiration,
+    escrow_nft: bool,
+    on_behalf_of: Option<String>,
 ) -> Result<Response, ContractError> {
     let ExecuteEnv { deps, info, env } = env;
 
@@ -77,39 +379,51 @@ pub fn try_create_trade(
 
     let cfg = CONFIG.load(deps.storage)?;
 
-    if cfg.e_break {
-        return Err(ContractError::EmergencyBreakActivated {});
+    if expires_at.is_expired(&env.block) {
+        return Err(ContractError::TradeExpired {});
     }
 
-    let amount_send = must_pay(&info, NATIVE_DENOM)?;
-
-    if cfg.listing_fee != amount_send {
-        return Err(ContractError::MissingListingFee {});
-    }
+    // coins the seller adds to a swap are escrowed along with the listing fee
+    let listing_fee = collect_listing_fee(&cfg, &info, &cw20_payment, &price, seller_coins)?;
 
     let nft_collection_addr = deps.api.addr_validate(&nft_addr)?;
-    // nonpayable(&info)?;
-    price_validate(&price)?;
-
-    // TODO: Find alternative to check if the owner of the NFT is calling this
-    only_owner(
-        deps.as_ref(),
-        &info,
-        &nft_collection_addr.clone(),
-        nft_id.to_string(),
-    )?;
 
-    Cw721Contract(nft_collection_addr.clone()).approval(
-        &deps.querier,
-        nft_id.to_string(),
-        env.contract.address.to_string(),
-        None,
-    )?;
+    // validate every NFT of the swap at once so none appears twice on either side
+    let offered_count = offered_nfts.len();
+    let mut nfts = vec![NftItem {
+        collection: nft_collection_addr.clone(),
+        token_id: nft_id.clone(),
+    }];
+    nfts.extend(offered_nfts);
+    nfts.extend(wanted_nfts);
+    let mut nfts = nfts_validate(deps.as_ref(), nfts)?;
+    let collections: Vec<&Addr> = nfts.iter().map(|nft| &nft.collection).collect();
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Create, &collections)?;
+    let wanted_nfts = nfts.split_off(offered_count + 1);
+    let offered_nfts = nfts.split_off(1);
+
+    // the seller is the sender, or the principal an operator creates the trade for
+    let seller = match on_behalf_of {
+        Some(principal) => deps.api.addr_validate(&principal)?,
+        None => info.sender.clone(),
+    };
+    // an operator scoped to some collections may only trade NFTs of those collections
+    let collections: Vec<&Addr> = nfts
+        .iter()
+        .chain(offered_nfts.iter())
+        .chain(wanted_nfts.iter())
+        .map(|nft| &nft.collection)
+        .collect();
+    only_principal_or_operator(deps.as_ref(), &env, &info.sender, &seller, &collections)?;
+
+    for nft in nfts.iter().chain(offered_nfts.iter()) {
+        check_nft_approval(deps.as_ref(), nft, &seller, &env.contract.address)?;
+    }
 
-    let seller = info.sender;
     let buyer = deps.api.addr_validate(&buyer_addr)?;
 
     let tradekey = trade_key(&buyer, &nft_collection_addr, nft_id.clone());
+    let fees = collection_fees(deps.as_ref(), &cfg, &nft_collection_addr)?;
 
     let trade = Trade {
         seller: seller.clone(),
@@ -117,30 +431,72 @@ pub fn try_create_trade(
         price: price.clone(),
         nft_collection: nft_collection_addr.clone(),
         nft_id,
-        seller_fee: cfg.seller_fee,
-        buyer_fee: cfg.buyer_fee,
+        seller_fee: fees.seller_fee,
+        buyer_fee: fees.buyer_fee,
         is_confirmed_trade: false,
+        offered_nfts,
+        wanted_nfts,
+        seller_coins,
+        expires_at,
+        nft_in_escrow: escrow_nft,
+        listing_fee: listing_fee.amount(),
     };
 
+    // an expired trade under the same key is pruned first, so what it holds goes back to the
+    // seller instead of being overwritten
+    if let Some(expired) = trades().may_load(deps.storage, tradekey.clone())? {
+        if !expired.expires_at.is_expired(&env.block) {
+            return Err(ContractError::TokenReserved {});
+        }
+        trades().remove(deps.storage, tradekey.clone())?;
+        release_tokens(deps.storage, &tradekey, &expired)?;
+        refund_seller(&expired, &mut res)?;
+        resolve_listing_fee(&expired, &expired.seller, "refunded", &mut res)?;
+
+        res.events.push(
+            Event::new("prune-expired-trade")
+                .add_attribute("collection", expired.nft_collection.to_string())
+                .add_attribute("nft_id", expired.nft_id.to_string())
+                .add_attribute("seller", expired.seller.to_string())
+                .add_attribute("buyer", expired.buyer.to_string()),
+        );
+    }
+
+    reserve_tokens(deps.storage, &env, &tradekey, &trade)?;
     trades().save(deps.storage, tradekey, &trade)?;
 
-    let event = Event::new("create-trade")
+    let mut event = Event::new("create-trade")
         .add_attribute("action", "try_create_trade")
         .add_attribute("seller", seller.to_string())
         .add_attribute("buyer", buyer.to_string())
-        .add_attribute("price", price.amount.to_string());
+        .add_attribute("price", price.amount().to_string())
+        .add_attribute("denom", price.denom());
+
+    if !trade.offered_nfts.is_empty() || !trade.wanted_nfts.is_empty() {
+        event = event
+            .add_attribute("offered_nfts", trade.seller_nfts().len().to_string())
+            .add_attribute("wanted_nfts", trade.wanted_nfts.len().to_string())
+            .add_attribute("seller_coins", seller_coins);
+    }
 
-    let transfer_fee_to_fee_admin = BankMsg::Send {
-        to_address: cfg.fee_admin.to_string(),
-        amount: vec![coin(cfg.listing_fee.u128(), NATIVE_DENOM)],
-    };
-    res = res.add_message(transfer_fee_to_fee_admin);
+    if expires_at != (Expiration::Never {}) {
+        event = event.add_attribute("expires_at", expires_at.to_string());
+    }
+
+    // custodial mode: the contract takes the seller's NFTs until the trade settles
+    if escrow_nft {
+        for nft in trade.seller_nfts().iter() {
+            res = res.add_message(transfer_nft_msg(nft, &env.contract.address)?);
+        }
+        event = event.add_attribute("nft_in_escrow", "true");
+    }
 
     Ok(res.add_event(event))
 }
 
 pub fn try_cancel_trade(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     buyer: Option<String>,
     seller: Option<String>,
@@ -149,10 +505,6 @@ pub fn try_cancel_trade(
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
-    if cfg.e_break {
-        return Err(ContractError::EmergencyBreakActivated {});
-    }
-
     nonpayable(&info)?;
 
     if let Some(buyer) = buyer {
@@ -164,120 +516,367 @@ pub fn try_cancel_trade(
             nft_id.clone(),
         );
         let trade = trades().load(deps.storage, key.clone())?;
-        if &trade.buyer != buyer_addr {
-            return Err(ContractError::Unauthorized {});
-        }
-
-        trades().remove(deps.storage, key)?;
+        ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Cancel, &trade.collections())?;
+        only_principal_or_operator(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            &trade.buyer,
+            &trade.collections(),
+        )?;
+
+        trades().remove(deps.storage, key.clone())?;
+        release_tokens(deps.storage, &key, &trade)?;
         let event = Event::new("buyer-cancels-trade")
             .add_attribute("collection", nft_collection)
             .add_attribute("nft_id", nft_id)
             .add_attribute("buyer", buyer);
 
-        let res = Response::new().add_event(event);
+        let mut res = Response::new().add_event(event);
+        refund_seller(&trade, &mut res)?;
+        resolve_listing_fee(&trade, &trade.seller, "refunded", &mut res)?;
 
         Ok(res)
     } else if let Some(seller) = seller {
-        let seller_addr = &deps.api.addr_validate(&seller)?;
-        let key = trade_key(
-            seller_addr,
-            &deps.api.addr_validate(&nft_collection)?,
-            nft_id.clone(),
-        );
-        let trade = trades().load(deps.storage, key.clone())?;
-        if &trade.seller != seller_addr {
-            return Err(ContractError::Unauthorized {});
-        }
-
-        trades().remove(deps.storage, key)?;
+        let seller_addr = deps.api.addr_validate(&seller)?;
+        let nft_collection_addr = deps.api.addr_validate(&nft_collection)?;
+
+        // trades are keyed by buyer, so the seller's trade is found through the token's reservation
+        let token = (nft_collection_addr.clone(), nft_id.clone());
+        let key = TOKEN_RESERVATIONS
+            .may_load(deps.storage, token)?
+            .ok_or_else(|| StdError::not_found("nftswitch::state::Trade"))?;
+        let trade = trades()
+            .may_load(deps.storage, key.clone())?
+            .filter(|trade| {
+                trade.seller == seller_addr
+                    && trade.nft_collection == nft_collection_addr
+                    && trade.nft_id == nft_id
+            })
+            .ok_or_else(|| StdError::not_found("nftswitch::state::Trade"))?;
+        ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Cancel, &trade.collections())?;
+        only_principal_or_operator(
+            deps.as_ref(),
+            &env,
+            &info.sender,
+            &trade.seller,
+            &trade.collections(),
+        )?;
+
+        trades().remove(deps.storage, key.clone())?;
+        release_tokens(deps.storage, &key, &trade)?;
         let event = Event::new("seller-cancels-trade")
             .add_attribute("collection", nft_collection)
             .add_attribute("nft_id", nft_id)
             .add_attribute("seller", seller);
 
-        let res = Response::new().add_event(event);
+        let mut res = Response::new().add_event(event);
+        refund_seller(&trade, &mut res)?;
+        resolve_listing_fee(&trade, &cfg.fee_admin, "forfeited", &mut res)?;
         Ok(res)
     } else {
-        return Err(ContractError::ParameterMissing {});
+        Err(ContractError::ParameterMissing {})
     }
 }
 
 pub fn try_execute_trade(
     env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
     buyer: String,
     nft_collection: String,
     nft_id: String,
+    fee_confirmation: Option<SignedFeeConfirmation>,
 ) -> Result<Response, ContractError> {
-    let ExecuteEnv { deps, info, env: _ } = env;
-
-    let mut res = Response::new();
+    let ExecuteEnv { mut deps, info, env } = env;
 
     let cfg = CONFIG.load(deps.storage)?;
 
-    if cfg.e_break {
-        return Err(ContractError::EmergencyBreakActivated {});
-    }
-
     let buyer = deps.api.addr_validate(&buyer)?;
     let nft_collection = deps.api.addr_validate(&nft_collection)?;
 
     // retrieve trade
     let trade_key = trade_key(&buyer, &nft_collection, nft_id.clone());
-    let trade = trades().load(deps.storage, trade_key.clone())?;
+    let mut trade = trades().load(deps.storage, trade_key.clone())?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Execute, &trade.collections())?;
+
+    // fees signed off-chain by the fee admin confirm the trade in the same transaction
+    if let Some(signed) = fee_confirmation {
+        if trade.is_confirmed_trade {
+            return Err(ContractError::AlreadyConfirmedFees {});
+        }
+        ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Confirm, &trade.collections())?;
+
+        verify_fee_confirmation(deps.as_ref(), &env, &cfg, &trade, &signed)?;
+        let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+        fee_caps_validate(
+            &bounds,
+            Some(signed.confirmation.buyer_fee_pct),
+            Some(signed.confirmation.seller_fee_pct),
+        )?;
+        FEE_CONFIRMATION_NONCES.save(deps.storage, signed.confirmation.nonce, &true)?;
+
+        trade.is_confirmed_trade = true;
+        trade.seller_fee = signed.confirmation.seller_fee_pct;
+        trade.buyer_fee = signed.confirmation.buyer_fee_pct;
+    }
 
     if !trade.is_confirmed_trade {
         return Err(ContractError::TradeNotConfirmed {});
     }
 
-    // verify sender is the buyer
-    if info.sender != trade.buyer {
-        return Err(ContractError::Unauthorized {});
+    if trade.expires_at.is_expired(&env.block) {
+        return Err(ContractError::TradeExpired {});
     }
 
-    // calculate commission
-    let buyer_fee = cfg.buyer_fee.clone() * trade.price.amount.clone();
-    let seller_fee = cfg.seller_fee.clone() * trade.price.amount.clone();
+    // verify sender is the buyer or one of their operators, the NFT still goes to the buyer
+    only_principal_or_operator(
+        deps.as_ref(),
+        &env,
+        &info.sender,
+        &trade.buyer,
+        &trade.collections(),
+    )?;
+
+    let mut res = settle_trade(&mut deps, &env, &info, &cw20_payment, &trade)?;
+
+    // remove trade from state
+    trades().remove(deps.storage, trade_key.clone())?;
+    release_tokens(deps.storage, &trade_key, &trade)?;
+    drop_conflicting_trades(deps.storage, &trade, &mut res)?;
+
+    Ok(res.add_attribute("method", "execute_trade"))
+}
+
+// collect the buyer's payment, then hand over NFTs, proceeds and commission
+fn settle_trade(
+    deps: &mut DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    cw20_payment: &Option<Cw20CoinVerified>,
+    trade: &Trade,
+) -> Result<Response, ContractError> {
+    let mut trade = trade.clone();
+    apply_holder_discounts(deps.as_ref(), &mut trade)?;
 
-    let expected_sent_amount = trade.price.amount.clone() + buyer_fee;
+    let expected_sent_amount = trade.price.amount() + trade.buyer_fee * trade.price.amount();
 
     let mut amount_send = Uint128::zero();
 
+    // payment is collected in the denom the trade was priced in
     if expected_sent_amount != Uint128::zero() {
-        amount_send = must_pay(&info, NATIVE_DENOM)?;
+        amount_send = must_pay_price(info, cw20_payment, &trade.price)?;
 
         if expected_sent_amount != amount_send {
             return Err(ContractError::PaymentAmountMismatch {});
         }
+    } else if cw20_payment.is_some() {
+        return Err(ContractError::PaymentAmountMismatch {});
+    } else {
+        // nothing is owed, so funds sent along would be stuck in the contract
+        nonpayable(info)?;
+    }
+
+    release_trade(deps, env, &trade, amount_send)
+}
+
+// holder discounts follow what each party holds when the trade settles
+fn apply_holder_discounts(deps: Deps, trade: &mut Trade) -> Result<(), ContractError> {
+    let buyer_discount = holder_discount(deps, &trade.buyer)?.buyer_discount;
+    let seller_discount = holder_discount(deps, &trade.seller)?.seller_discount;
+    trade.buyer_fee *= Decimal::one() - buyer_discount;
+    trade.seller_fee *= Decimal::one() - seller_discount;
+    Ok(())
+}
+
+// hand over NFTs, proceeds and commission once the buyer's payment is held by the contract.
+// Whatever the contract holds above the price and buyer fee goes back to the buyer.
+fn release_trade(
+    deps: &mut DepsMut,
+    env: &Env,
+    trade: &Trade,
+    amount_send: Uint128,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+
+    // both sides must still hold and have approved every NFT they hand over,
+    // unless the contract already holds the seller's
+    if !trade.nft_in_escrow {
+        for nft in trade.seller_nfts().iter() {
+            check_nft_approval(deps.as_ref(), nft, &trade.seller, &env.contract.address)?;
+        }
+    }
+    for nft in trade.wanted_nfts.iter() {
+        check_nft_approval(deps.as_ref(), nft, &trade.buyer, &env.contract.address)?;
     }
+
+    // calculate commission at the rates confirmed by the fee admin
+    let buyer_fee = trade.buyer_fee * trade.price.amount();
+    let seller_fee = trade.seller_fee * trade.price.amount();
     let commission = buyer_fee + seller_fee;
 
+    let royalty = royalty_for(deps.as_ref(), trade)?;
+    let royalty_amount = royalty.as_ref().map(|(_, amount)| *amount).unwrap_or_default();
+
+    // an escrow taken at the full buyer fee returns the holder discount to the buyer
+    let due = trade.price.amount() + buyer_fee;
+    let overpaid = amount_send.checked_sub(due).unwrap_or_default();
+    if !overpaid.is_zero() {
+        let refund_msg = transfer_price_msg(&trade.buyer, &trade.price.with_amount(overpaid))?;
+        res.messages.push(SubMsg::new(refund_msg));
+    }
+    let amount_send = amount_send - overpaid;
+
     // send NFT
-    transfer_nft(&trade, &mut res)?;
+    transfer_nft(trade, &mut res)?;
+
+    // send royalty to the creator, out of the seller's proceeds
+    if let Some((recipient, amount)) = &royalty {
+        let royalty_msg = transfer_price_msg(recipient, &trade.price.with_amount(*amount))?;
+        res.messages.push(SubMsg::new(royalty_msg));
+        res = res.add_attribute("royalty_recipient", recipient.to_string());
+    }
 
     // send amount to seller
     if amount_send != Uint128::zero() {
-        transfer_coin_to_seller(&trade, amount_send.checked_sub(commission).unwrap(), &mut res)?;
+        let seller_amount = amount_send
+            .checked_sub(commission)
+            .unwrap()
+            .checked_sub(royalty_amount)
+            .map_err(|_| ContractError::InvalidRoyalty {})?;
+        transfer_coin_to_seller(trade, seller_amount, &mut res)?;
     }
 
     // send commission
     if commission != Uint128::zero() {
-        transfer_commission(&deps, commission.clone(), &mut res)?;
+        transfer_commission(deps, trade.price.with_amount(commission), &mut res)?;
     }
 
-    // remove trade from state
-    trades().remove(deps.storage, trade_key)?;
+    // release the coins the seller added to the swap
+    if !trade.seller_coins.is_zero() {
+        let seller_coins_msg =
+            transfer_price_msg(&trade.buyer, &trade.price.with_amount(trade.seller_coins))?;
+        res.messages.push(SubMsg::new(seller_coins_msg));
+    }
+
+    // the listing fee held since creation is earned by the fee admin
+    let cfg = CONFIG.load(deps.storage)?;
+    resolve_listing_fee(trade, &cfg.fee_admin, "released", &mut res)?;
+
+    let sale_id = record_sale(deps.storage, env, trade, buyer_fee, seller_fee, royalty_amount)?;
+    update_stats(deps.storage, trade)?;
 
     Ok(res
-        .add_attribute("method", "execute_trade")
+        .add_attribute("sale_id", sale_id.to_string())
         .add_attribute("buyer", trade.buyer.to_string())
         .add_attribute("buyer_fee", buyer_fee)
         .add_attribute("seller", trade.seller.to_string())
         .add_attribute("seller_fee", seller_fee)
         .add_attribute("commission", commission)
+        .add_attribute("royalty", royalty_amount)
+        .add_attribute("denom", trade.price.denom())
         .add_attribute("nft_collection", trade.nft_collection.to_string())
         .add_attribute("nft_id", trade.nft_id.to_string()))
 }
 
+// append the settled trade to the sales ledger, one entry per token that changed hands.
+// Returns the id of the priced token's entry.
+fn record_sale(
+    storage: &mut dyn Storage,
+    env: &Env,
+    trade: &Trade,
+    buyer_fee: Uint128,
+    seller_fee: Uint128,
+    royalty: Uint128,
+) -> StdResult<u64> {
+    let id = SALE_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    let sale = Sale {
+        id,
+        seller: trade.seller.clone(),
+        buyer: trade.buyer.clone(),
+        nft_collection: trade.nft_collection.clone(),
+        nft_id: trade.nft_id.clone(),
+        price: trade.price.clone(),
+        buyer_fee,
+        seller_fee,
+        royalty,
+        timestamp: env.block.time,
+        part_of: None,
+    };
+    sales().save(storage, id, &sale)?;
+
+    // the buyer's NFTs of a swap move the other way
+    let moved = trade
+        .offered_nfts
+        .iter()
+        .map(|nft| (nft, &trade.seller, &trade.buyer))
+        .chain(trade.wanted_nfts.iter().map(|nft| (nft, &trade.buyer, &trade.seller)));
+    let mut last_id = id;
+    for (nft, from, to) in moved {
+        last_id += 1;
+        let part = Sale {
+            id: last_id,
+            seller: from.clone(),
+            buyer: to.clone(),
+            nft_collection: nft.collection.clone(),
+            nft_id: nft.token_id.clone(),
+            price: trade.price.with_amount(Uint128::zero()),
+            buyer_fee: Uint128::zero(),
+            seller_fee: Uint128::zero(),
+            royalty: Uint128::zero(),
+            timestamp: env.block.time,
+            part_of: Some(id),
+        };
+        sales().save(storage, last_id, &part)?;
+    }
+    SALE_COUNT.save(storage, &last_id)?;
+
+    Ok(id)
+}
+
+// add the sale to the global, collection, seller and buyer aggregates in its denom
+fn update_stats(storage: &mut dyn Storage, trade: &Trade) -> StdResult<()> {
+    let denom = trade.price.denom();
+    let price = trade.price.amount();
+    let record = |stats: Option<TradeStats>| -> StdResult<TradeStats> {
+        let mut stats = stats.unwrap_or_else(|| TradeStats::new(denom.clone()));
+        stats.record(price);
+        Ok(stats)
+    };
+
+    GLOBAL_STATS.update(storage, denom.clone(), record)?;
+    COLLECTION_STATS.update(storage, (trade.nft_collection.clone(), denom.clone()), record)?;
+    SELLER_STATS.update(storage, (trade.seller.clone(), denom.clone()), record)?;
+    BUYER_STATS.update(storage, (trade.buyer.clone(), denom.clone()), record)?;
+
+    Ok(())
+}
+
+// royalty owed to the creator under the collection's policy, as (recipient, amount)
+fn royalty_for(deps: Deps, trade: &Trade) -> Result<Option<(Addr, Uint128)>, ContractError> {
+    let policy = ROYALTY_POLICIES
+        .may_load(deps.storage, trade.nft_collection.clone())?
+        .unwrap_or_default();
+    if policy == RoyaltyPolicy::Ignore || trade.price.amount().is_zero() {
+        return Ok(None);
+    }
+
+    let royalty = match query_royalty(
+        deps,
+        &trade.nft_collection,
+        trade.nft_id.clone(),
+        trade.price.amount(),
+    ) {
+        Ok(royalty) => royalty,
+        Err(_) if policy == RoyaltyPolicy::Honor => return Ok(None),
+        Err(_) => return Err(ContractError::RoyaltyUnavailable {}),
+    };
+    if royalty.royalty_amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some((deps.api.addr_validate(&royalty.address)?, royalty.royalty_amount)))
+}
+
 pub fn try_confirm_trade(
     env: ExecuteEnv,
     buyer: String,
@@ -287,17 +886,13 @@ pub fn try_confirm_trade(
     buyer_fee_pct: Decimal,
     is_confirmed_by_fee_admin: bool,
 ) -> Result<Response, ContractError> {
-    let ExecuteEnv { deps, info, env: _ } = env;
+    let ExecuteEnv { deps, info, env } = env;
 
     let cfg = CONFIG.load(deps.storage)?;
 
     nonpayable(&info)?;
 
-    if cfg.e_break {
-        return Err(ContractError::EmergencyBreakActivated {});
-    }
-
-    if info.sender != cfg.fee_admin {
+    if !has_role(deps.as_ref(), &cfg, Role::TradeConfirmer, &info.sender)? {
         return Err(ContractError::UnauthorizedOwner {});
     }
 
@@ -311,16 +906,30 @@ pub fn try_confirm_trade(
     // reason to not confirm the trade, so the trade is removed.
     if !is_confirmed_by_fee_admin {
         trades().remove(deps.storage, trade_key.clone())?;
-        Ok(Response::new().add_attribute("method", "remove_trade"))
+        release_tokens(deps.storage, &trade_key, &trade)?;
+        let mut res = Response::new().add_attribute("method", "remove_trade");
+        refund_seller(&trade, &mut res)?;
+        resolve_listing_fee(&trade, &trade.seller, "refunded", &mut res)?;
+        Ok(res)
     } else {
+        // rejecting refunds the seller, so only confirmations are paused
+        ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Confirm, &trade.collections())?;
+
         if trade.is_confirmed_trade {
             return Err(ContractError::AlreadyConfirmedFees {});
         }
 
+        if trade.expires_at.is_expired(&env.block) {
+            return Err(ContractError::TradeExpired {});
+        }
+
+        let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+        fee_caps_validate(&bounds, Some(buyer_fee_pct), Some(seller_fee_pct))?;
+
         trade.is_confirmed_trade = true;
 
-        trade.buyer_fee = Decimal::from(buyer_fee_pct);
-        trade.seller_fee = Decimal::from(seller_fee_pct);
+        trade.buyer_fee = buyer_fee_pct;
+        trade.seller_fee = seller_fee_pct;
 
         trades().save(deps.storage, trade_key, &trade)?;
 
@@ -328,55 +937,1348 @@ pub fn try_confirm_trade(
     }
 }
 
-fn transfer_nft(trade: &Trade, res: &mut Response) -> StdResult<()> {
-    let cw721_transfer_msg = Cw721ExecuteMsg::TransferNft {
-        token_id: trade.nft_id.to_string(),
-        recipient: trade.buyer.to_string(),
-    };
+// anyone may clear out expired trades, the seller gets back whatever they escrowed
+pub fn try_prune_expired_trades(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+    start_after: Option<TradeOffset>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
 
-    let exec_cw721_transfer = WasmMsg::Execute {
-        contract_addr: trade.nft_collection.to_string(),
-        msg: to_binary(&cw721_transfer_msg)?,
-        funds: vec![],
+    let limit = limit.unwrap_or(DEFAULT_PRUNE_LIMIT).min(MAX_PRUNE_LIMIT) as usize;
+    let start = trade_offset_bound(deps.api, start_after)?;
+
+    // every trade read counts against the limit, live ones included, so each call stays bounded
+    let page = trades()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next = match page.last() {
+        Some((key, _)) if page.len() == limit => Some(key.clone()),
+        _ => None,
     };
-    res.messages.push(SubMsg::new(exec_cw721_transfer));
+    let expired: Vec<_> = page
+        .into_iter()
+        .filter(|(_, trade)| trade.expires_at.is_expired(&env.block))
+        .collect();
 
-    let event = Event::new("finalize-switch")
-        .add_attribute("nft_collection", trade.nft_collection.to_string())
-        .add_attribute("nft_id", trade.nft_id.to_string())
-        .add_attribute("seller", trade.seller.to_string())
-        .add_attribute("buyer", trade.buyer.to_string())
-        .add_attribute("price", trade.price.to_string());
+    let mut res = Response::new();
+    for (key, trade) in expired.iter() {
+        trades().remove(deps.storage, key.clone())?;
+        release_tokens(deps.storage, key, trade)?;
+        refund_seller(trade, &mut res)?;
+        resolve_listing_fee(trade, &trade.seller, "refunded", &mut res)?;
+
+        res.events.push(
+            Event::new("prune-expired-trade")
+                .add_attribute("collection", trade.nft_collection.to_string())
+                .add_attribute("nft_id", trade.nft_id.to_string())
+                .add_attribute("seller", trade.seller.to_string())
+                .add_attribute("buyer", trade.buyer.to_string()),
+        );
+    }
 
-    res.events.push(event);
+    res = res
+        .add_attribute("method", "prune_expired_trades")
+        .add_attribute("pruned", expired.len().to_string());
+    if let Some((buyer, nft_collection, nft_id)) = next {
+        res = res
+            .add_attribute("next_buyer", buyer.to_string())
+            .add_attribute("next_nft_collection", nft_collection.to_string())
+            .add_attribute("next_nft_id", nft_id);
+    }
 
-    Ok(())
+    Ok(res)
 }
 
-// send amount to seller
-fn transfer_coin_to_seller(trade: &Trade, amount: Uint128, res: &mut Response) -> StdResult<()> {
-    let transfer_amount: Coin = Coin::new(u128::from(amount), NATIVE_DENOM);
+pub fn try_register_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+    expires_at: Option<Expiration>,
+    collections: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
 
-    let seller_transfer_msg = BankMsg::Send {
-        to_address: trade.seller.to_string(),
-        amount: vec![transfer_amount],
+    if cfg.e_break {
+        return Err(ContractError::EmergencyBreakActivated {});
+    }
+
+    nonpayable(&info)?;
+
+    let operator = deps.api.addr_validate(&operator)?;
+    if operator == info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let key = (info.sender.clone(), operator.clone());
+    if OPERATORS.has(deps.storage, key.clone()) {
+        return Err(ContractError::OperatorAlreadyRegistered {});
+    }
+
+    let collections = collections
+        .unwrap_or_default()
+        .iter()
+        .map(|collection| deps.api.addr_validate(collection))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let registration = Operator {
+        principal: info.sender.clone(),
+        operator: operator.clone(),
+        expires_at: expires_at.unwrap_or_default(),
+        collections,
     };
-    res.messages.push(SubMsg::new(seller_transfer_msg));
-    Ok(())
+    OPERATORS.save(deps.storage, key, &registration)?;
+
+    let event = Event::new("register-operator")
+        .add_attribute("principal", info.sender.to_string())
+        .add_attribute("operator", operator.to_string())
+        .add_attribute("expires_at", registration.expires_at.to_string())
+        .add_attribute("collections", registration.collections.len().to_string());
+
+    Ok(Response::new().add_event(event))
 }
 
-// send commission
-fn transfer_commission(deps: &DepsMut, commission: Uint128, res: &mut Response) -> StdResult<()> {
+// revoking stays available with the emergency break on
+pub fn try_revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let operator = deps.api.addr_validate(&operator)?;
+    let key = (info.sender.clone(), operator.clone());
+    if !OPERATORS.has(deps.storage, key.clone()) {
+        return Err(ContractError::OperatorNotRegistered {});
+    }
+    OPERATORS.remove(deps.storage, key);
+
+    let event = Event::new("revoke-operator")
+        .add_attribute("principal", info.sender.to_string())
+        .add_attribute("operator", operator.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn try_set_royalty_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: String,
+    policy: RoyaltyPolicy,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::CollectionCurator, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let collection = deps.api.addr_validate(&collection)?;
+    ROYALTY_POLICIES.save(deps.storage, collection.clone(), &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_royalty_policy")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("policy", format!("{:?}", policy).to_lowercase()))
+}
+
+pub fn try_set_collection_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    buyer_fee: Decimal,
+    seller_fee: Decimal,
+    partner: bool,
+) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+    fee_caps_validate(&bounds, Some(buyer_fee), Some(seller_fee))?;
+
+    if partner && (buyer_fee > cfg.buyer_fee || seller_fee > cfg.seller_fee) {
+        return Err(ContractError::InvalidPartnerFees {});
+    }
+
+    let collection = deps.api.addr_validate(&collection)?;
+    let fees = FeeSchedule {
+        buyer_fee,
+        seller_fee,
+        partner,
+    };
+    let res = Response::new()
+        .add_attribute("action", "set_collection_fees")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("buyer_fee", buyer_fee.to_string())
+        .add_attribute("seller_fee", seller_fee.to_string())
+        .add_attribute("partner", partner.to_string());
+
+    update_collection_fees(deps, &env, &cfg, collection, Some(fees), res)
+}
+
+pub fn try_remove_collection_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let collection = deps.api.addr_validate(&collection)?;
+    let res = Response::new()
+        .add_attribute("action", "remove_collection_fees")
+        .add_attribute("collection", collection.to_string());
+
+    update_collection_fees(deps, &env, &cfg, collection, None, res)
+}
+
+// Fee cuts apply at once, a change raising either rate waits out the timelock like the
+// global fees. The latest change replaces whatever was pending for the collection.
+fn update_collection_fees(
+    deps: DepsMut,
+    env: &Env,
+    cfg: &Config,
+    collection: Addr,
+    fees: Option<FeeSchedule>,
+    res: Response,
+) -> Result<Response, ContractError> {
+    let current = collection_fees(deps.as_ref(), cfg, &collection)?;
+    let (buyer_fee, seller_fee) = match &fees {
+        Some(fees) => (fees.buyer_fee, fees.seller_fee),
+        None => (cfg.buyer_fee, cfg.seller_fee),
+    };
+    PENDING_COLLECTION_FEE_CHANGES.remove(deps.storage, collection.clone());
+
+    if buyer_fee > current.buyer_fee || seller_fee > current.seller_fee {
+        let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+        let change = PendingCollectionFeeChange {
+            fees,
+            effective_at: env.block.time.plus_seconds(bounds.increase_delay),
+        };
+        PENDING_COLLECTION_FEE_CHANGES.save(deps.storage, collection, &change)?;
+        return Ok(res.add_attribute("fee_change_effective_at", change.effective_at.to_string()));
+    }
+
+    match fees {
+        Some(fees) => COLLECTION_FEES.save(deps.storage, collection, &fees)?,
+        None => COLLECTION_FEES.remove(deps.storage, collection),
+    }
+
+    Ok(res)
+}
+
+pub fn try_set_holder_discount(
+    deps: DepsMut,
+    info: MessageInfo,
+    membership_collection: String,
+    buyer_discount: Decimal,
+    seller_discount: Decimal,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    if buyer_discount > Decimal::one() || seller_discount > Decimal::one() {
+        return Err(ContractError::InvalidDiscount {});
+    }
+
+    let membership_collection = deps.api.addr_validate(&membership_collection)?;
+    if !HOLDER_DISCOUNTS.has(deps.storage, membership_collection.clone()) {
+        let count = HOLDER_DISCOUNTS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .count();
+        if count >= MAX_HOLDER_DISCOUNTS as usize {
+            return Err(ContractError::TooManyHolderDiscounts {
+                max: MAX_HOLDER_DISCOUNTS,
+            });
+        }
+    }
+    let discount = FeeDiscount {
+        buyer_discount,
+        seller_discount,
+    };
+    HOLDER_DISCOUNTS.save(deps.storage, membership_collection.clone(), &discount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_holder_discount")
+        .add_attribute("membership_collection", membership_collection.to_string())
+        .add_attribute("buyer_discount", buyer_discount.to_string())
+        .add_attribute("seller_discount", seller_discount.to_string()))
+}
+
+pub fn try_remove_holder_discount(
+    deps: DepsMut,
+    info: MessageInfo,
+    membership_collection: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let membership_collection = deps.api.addr_validate(&membership_collection)?;
+    HOLDER_DISCOUNTS.remove(deps.storage, membership_collection.clone());
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_holder_discount")
+        .add_attribute("membership_collection", membership_collection.to_string()))
+}
+
+pub fn try_set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    action: PauseAction,
+    paused: bool,
+    collection: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::Pauser, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("pause_action", action.as_str())
+        .add_attribute("paused", paused.to_string());
+
+    match collection {
+        Some(collection) => {
+            let collection = deps.api.addr_validate(&collection)?;
+            let mut pauses = COLLECTION_PAUSES
+                .may_load(deps.storage, collection.clone())?
+                .unwrap_or_default();
+            pauses.set(action, paused);
+            if pauses == Default::default() {
+                COLLECTION_PAUSES.remove(deps.storage, collection.clone());
+            } else {
+                COLLECTION_PAUSES.save(deps.storage, collection.clone(), &pauses)?;
+            }
+            res = res.add_attribute("collection", collection.to_string());
+        }
+        None => {
+            cfg.pauses.set(action, paused);
+            CONFIG.save(deps.storage, &cfg)?;
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn try_set_fee_bounds(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_buyer_fee: Decimal,
+    max_seller_fee: Decimal,
+    increase_delay: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    // caps may not strand the fees already in force
+    if max_buyer_fee >= Decimal::one()
+        || max_seller_fee >= Decimal::one()
+        || max_buyer_fee < cfg.buyer_fee
+        || max_seller_fee < cfg.seller_fee
+    {
+        return Err(ContractError::InvalidFeeCap {});
+    }
+    for fees in COLLECTION_FEES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, fees) = fees?;
+        if fees.buyer_fee > max_buyer_fee || fees.seller_fee > max_seller_fee {
+            return Err(ContractError::InvalidFeeCap {});
+        }
+    }
+
+    let bounds = FeeBounds {
+        max_buyer_fee,
+        max_seller_fee,
+        increase_delay,
+    };
+    FEE_BOUNDS.save(deps.storage, &bounds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_bounds")
+        .add_attribute("max_buyer_fee", max_buyer_fee.to_string())
+        .add_attribute("max_seller_fee", max_seller_fee.to_string())
+        .add_attribute("increase_delay", increase_delay.to_string()))
+}
+
+// anyone may apply a fee increase once its timelock has passed
+pub fn try_apply_fee_change(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let change = PENDING_FEE_CHANGE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingFeeChange {})?;
+    if env.block.time < change.effective_at {
+        return Err(ContractError::FeeChangeLocked {
+            effective_at: change.effective_at,
+        });
+    }
+
+    // the caps may have been lowered since the change was scheduled
+    let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+    fee_caps_validate(&bounds, change.buyer_fee, change.seller_fee)?;
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    cfg.buyer_fee = change.buyer_fee.unwrap_or(cfg.buyer_fee);
+    cfg.seller_fee = change.seller_fee.unwrap_or(cfg.seller_fee);
+    CONFIG.save(deps.storage, &cfg)?;
+    PENDING_FEE_CHANGE.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "apply_fee_change")
+        .add_attribute("buyer_fee", cfg.buyer_fee.to_string())
+        .add_attribute("seller_fee", cfg.seller_fee.to_string()))
+}
+
+pub fn try_cancel_fee_change(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    if PENDING_FEE_CHANGE.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingFeeChange {});
+    }
+    PENDING_FEE_CHANGE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_fee_change"))
+}
+
+// anyone may apply a collection fee increase once its timelock has passed
+pub fn try_apply_collection_fee_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let collection = deps.api.addr_validate(&collection)?;
+    let change = PENDING_COLLECTION_FEE_CHANGES
+        .may_load(deps.storage, collection.clone())?
+        .ok_or(ContractError::NoPendingFeeChange {})?;
+    if env.block.time < change.effective_at {
+        return Err(ContractError::FeeChangeLocked {
+            effective_at: change.effective_at,
+        });
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "apply_collection_fee_change")
+        .add_attribute("collection", collection.to_string());
+    match change.fees {
+        Some(fees) => {
+            // the caps and global fees may have moved since the change was scheduled
+            let cfg = CONFIG.load(deps.storage)?;
+            let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+            fee_caps_validate(&bounds, Some(fees.buyer_fee), Some(fees.seller_fee))?;
+            if fees.partner && (fees.buyer_fee > cfg.buyer_fee || fees.seller_fee > cfg.seller_fee) {
+                return Err(ContractError::InvalidPartnerFees {});
+            }
+
+            res = res
+                .add_attribute("buyer_fee", fees.buyer_fee.to_string())
+                .add_attribute("seller_fee", fees.seller_fee.to_string());
+            COLLECTION_FEES.save(deps.storage, collection.clone(), &fees)?;
+        }
+        None => COLLECTION_FEES.remove(deps.storage, collection.clone()),
+    }
+    PENDING_COLLECTION_FEE_CHANGES.remove(deps.storage, collection);
+
+    Ok(res)
+}
+
+pub fn try_cancel_collection_fee_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    only_role(deps.as_ref(), &cfg, Role::FeeSetter, &info.sender)?;
+
+    nonpayable(&info)?;
+
+    let collection = deps.api.addr_validate(&collection)?;
+    if PENDING_COLLECTION_FEE_CHANGES
+        .may_load(deps.storage, collection.clone())?
+        .is_none()
+    {
+        return Err(ContractError::NoPendingFeeChange {});
+    }
+    PENDING_COLLECTION_FEE_CHANGES.remove(deps.storage, collection.clone());
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_collection_fee_change")
+        .add_attribute("collection", collection.to_string()))
+}
+
+pub fn try_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.as_str().to_string(), address.clone()), &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address.to_string()))
+}
+
+pub fn try_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, (role.as_str().to_string(), address.clone()));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address.to_string()))
+}
+
+/// Config roles that change hands through propose / accept
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdminRole {
+    Admin,
+    FeeAdmin,
+}
+
+impl AdminRole {
+    fn name(&self) -> &'static str {
+        match self {
+            AdminRole::Admin => "admin",
+            AdminRole::FeeAdmin => "fee_admin",
+        }
+    }
+
+    fn pending<'a>(&self, cfg: &'a mut Config) -> &'a mut Option<PendingTransfer> {
+        match self {
+            AdminRole::Admin => &mut cfg.pending_admin,
+            AdminRole::FeeAdmin => &mut cfg.pending_fee_admin,
+        }
+    }
+
+    fn holder<'a>(&self, cfg: &'a mut Config) -> &'a mut Addr {
+        match self {
+            AdminRole::Admin => &mut cfg.admin,
+            AdminRole::FeeAdmin => &mut cfg.fee_admin,
+        }
+    }
+}
+
+pub fn try_propose_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    role: AdminRole,
+    new_address: String,
+    expires_at: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    let new_address = deps.api.addr_validate(&new_address)?;
+    let expires_at = expires_at.unwrap_or_default();
+    if expires_at.is_expired(&env.block) {
+        return Err(ContractError::TransferExpired {});
+    }
+
+    // a new proposal replaces the pending one
+    *role.pending(&mut cfg) = Some(PendingTransfer {
+        new_address: new_address.clone(),
+        expires_at,
+    });
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("role-transfer")
+                .add_attribute("role", role.name())
+                .add_attribute("stage", "proposed")
+                .add_attribute("new_address", new_address.to_string()),
+        )
+        .add_attribute("action", "propose_transfer")
+        .add_attribute("role", role.name())
+        .add_attribute("new_address", new_address.to_string())
+        .add_attribute("expires_at", expires_at.to_string()))
+}
+
+pub fn try_accept_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    role: AdminRole,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    nonpayable(&info)?;
+
+    let pending = role.pending(&mut cfg).take().ok_or(ContractError::NoPendingTransfer {})?;
+    if info.sender != pending.new_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pending.expires_at.is_expired(&env.block) {
+        return Err(ContractError::TransferExpired {});
+    }
+
+    let previous = std::mem::replace(role.holder(&mut cfg), pending.new_address.clone());
+    // signatures of the outgoing fee admin must not confirm trades anymore
+    if role == AdminRole::FeeAdmin {
+        cfg.fee_admin_pubkey = None;
+    }
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("role-transfer")
+                .add_attribute("role", role.name())
+                .add_attribute("stage", "accepted")
+                .add_attribute("new_address", pending.new_address.to_string()),
+        )
+        .add_attribute("action", "accept_transfer")
+        .add_attribute("role", role.name())
+        .add_attribute("previous", previous.to_string())
+        .add_attribute("new_address", pending.new_address.to_string()))
+}
+
+pub fn try_cancel_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: AdminRole,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    let pending = role.pending(&mut cfg).take().ok_or(ContractError::NoPendingTransfer {})?;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("role-transfer")
+                .add_attribute("role", role.name())
+                .add_attribute("stage", "cancelled")
+                .add_attribute("new_address", pending.new_address.to_string()),
+        )
+        .add_attribute("action", "cancel_transfer")
+        .add_attribute("role", role.name()))
+}
+
+pub fn try_renounce_admin(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    nonpayable(&info)?;
+
+    // the contract never sends admin messages to itself, so this locks administration for good
+    cfg.admin = env.contract.address;
+    cfg.pending_admin = None;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("role-transfer")
+                .add_attribute("role", AdminRole::Admin.name())
+                .add_attribute("stage", "renounced"),
+        )
+        .add_attribute("action", "renounce_admin"))
+}
+
+pub fn try_create_listing(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_addr: String,
+    nft_id: String,
+    price: TradePrice,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { deps, info, env } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // the listing fee is held until the listing sells or is taken down
+    let listing_fee = collect_listing_fee(&cfg, &info, &cw20_payment, &price, Uint128::zero())?;
+
+    let nft_collection_addr = deps.api.addr_validate(&nft_addr)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Create, &[&nft_collection_addr])?;
+
+    only_owner(
+        deps.as_ref(),
+        &info,
+        &nft_collection_addr.clone(),
+        nft_id.to_string(),
+    )?;
+
+    Cw721Contract(nft_collection_addr.clone()).approval(
+        &deps.querier,
+        nft_id.to_string(),
+        env.contract.address.to_string(),
+        None,
+    )?;
+
+    let fees = collection_fees(deps.as_ref(), &cfg, &nft_collection_addr)?;
+    let listing = Listing {
+        seller: info.sender.clone(),
+        price: price.clone(),
+        nft_collection: nft_collection_addr.clone(),
+        nft_id: nft_id.clone(),
+        is_confirmed_listing: false,
+        seller_fee: fees.seller_fee,
+        buyer_fee: fees.buyer_fee,
+        listing_fee: listing_fee.amount(),
+    };
+
+    listings().save(deps.storage, listing_key(&nft_collection_addr, nft_id.clone()), &listing)?;
+
+    let event = Event::new("create-listing")
+        .add_attribute("action", "try_create_listing")
+        .add_attribute("seller", info.sender.to_string())
+        .add_attribute("nft_collection", nft_collection_addr.to_string())
+        .add_attribute("nft_id", nft_id)
+        .add_attribute("price", price.amount().to_string())
+        .add_attribute("denom", price.denom());
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn try_cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_collection: String,
+    nft_id: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    nonpayable(&info)?;
+
+    let nft_collection_addr = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Cancel, &[&nft_collection_addr])?;
+
+    let key = listing_key(&nft_collection_addr, nft_id.clone());
+    let listing = listings().load(deps.storage, key.clone())?;
+    if listing.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    listings().remove(deps.storage, key)?;
+    let mut res = Response::new();
+    resolve_unsold_listing_fee(&listing, &listing.seller, "refunded", &mut res)?;
+    let event = Event::new("seller-cancels-listing")
+        .add_attribute("collection", nft_collection)
+        .add_attribute("nft_id", nft_id)
+        .add_attribute("seller", listing.seller.to_string());
+
+    Ok(res.add_event(event))
+}
+
+pub fn try_confirm_listing(
+    env: ExecuteEnv,
+    nft_collection: String,
+    nft_id: String,
+    seller_fee_pct: Decimal,
+    buyer_fee_pct: Decimal,
+    is_confirmed_by_fee_admin: bool,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { deps, info, env: _ } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    nonpayable(&info)?;
+
+    if !has_role(deps.as_ref(), &cfg, Role::TradeConfirmer, &info.sender)? {
+        return Err(ContractError::UnauthorizedOwner {});
+    }
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    let key = listing_key(&nft_collection, nft_id);
+    let mut listing = listings().load(deps.storage, key.clone())?;
+
+    if !is_confirmed_by_fee_admin {
+        listings().remove(deps.storage, key)?;
+        let mut res = Response::new();
+        resolve_unsold_listing_fee(&listing, &listing.seller, "refunded", &mut res)?;
+        Ok(res.add_attribute("method", "remove_listing"))
+    } else {
+        ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Confirm, &[&nft_collection])?;
+
+        if listing.is_confirmed_listing {
+            return Err(ContractError::AlreadyConfirmedFees {});
+        }
+
+        let bounds = FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default();
+        fee_caps_validate(&bounds, Some(buyer_fee_pct), Some(seller_fee_pct))?;
+
+        listing.is_confirmed_listing = true;
+
+        listing.buyer_fee = buyer_fee_pct;
+        listing.seller_fee = seller_fee_pct;
+
+        listings().save(deps.storage, key, &listing)?;
+
+        Ok(Response::new().add_attribute("method", "update_listing_fees"))
+    }
+}
+
+pub fn try_execute_listing(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_collection: String,
+    nft_id: String,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { mut deps, info, env } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Execute, &[&nft_collection])?;
+
+    let key = listing_key(&nft_collection, nft_id);
+    let listing = listings().load(deps.storage, key.clone())?;
+
+    if !listing.is_confirmed_listing {
+        return Err(ContractError::ListingNotConfirmed {});
+    }
+
+    if info.sender == listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // whoever takes the listing becomes the buyer
+    let trade = listing.into_trade(info.sender.clone());
+    let mut res = settle_trade(&mut deps, &env, &info, &cw20_payment, &trade)?;
+
+    listings().remove(deps.storage, key)?;
+    drop_conflicting_trades(deps.storage, &trade, &mut res)?;
+
+    Ok(res.add_attribute("method", "execute_listing"))
+}
+
+pub fn try_create_offer(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_collection: String,
+    nft_id: String,
+    price: TradePrice,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { deps, info, env: _ } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    price_validate(&price, &cfg)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Create, &[&nft_collection])?;
+
+    // the token must exist and belong to someone else
+    let owner = Cw721Contract(nft_collection.clone()).owner_of(&deps.querier, nft_id.clone(), false)?;
+    if owner.owner == info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let key = offer_key(&nft_collection, nft_id.clone(), &info.sender);
+    if offers().has(deps.storage, key.clone()) {
+        return Err(ContractError::OfferAlreadyExists {});
+    }
+
+    let fees = collection_fees(deps.as_ref(), &cfg, &nft_collection)?;
+    let offer = Offer {
+        bidder: info.sender.clone(),
+        price: price.clone(),
+        nft_collection: nft_collection.clone(),
+        nft_id: nft_id.clone(),
+        seller_fee: fees.seller_fee,
+        buyer_fee: fees.buyer_fee,
+    };
+
+    // the bidder escrows the price plus their fee
+    let escrow = offer.escrow();
+    must_pay_escrow(&info, &cw20_payment, &price, escrow)?;
+
+    offers().save(deps.storage, key, &offer)?;
+
+    let event = Event::new("create-offer")
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("nft_collection", nft_collection.to_string())
+        .add_attribute("nft_id", nft_id)
+        .add_attribute("price", price.amount().to_string())
+        .add_attribute("denom", price.denom())
+        .add_attribute("escrow", escrow);
+
+    Ok(Response::new().add_event(event))
+}
+
+// the bidder can take back the escrow at any time before acceptance, even with the emergency break on
+pub fn try_withdraw_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_collection: String,
+    nft_id: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    let key = offer_key(&nft_collection, nft_id.clone(), &info.sender);
+    let offer = offers().load(deps.storage, key.clone())?;
+
+    offers().remove(deps.storage, key)?;
+
+    let escrow = offer.escrow();
+    let mut res = Response::new();
+    if !escrow.is_zero() {
+        let refund_msg = transfer_price_msg(&offer.bidder, &offer.price.with_amount(escrow))?;
+        res.messages.push(SubMsg::new(refund_msg));
+    }
+
+    let event = Event::new("bidder-withdraws-offer")
+        .add_attribute("collection", nft_collection.to_string())
+        .add_attribute("nft_id", nft_id)
+        .add_attribute("bidder", offer.bidder.to_string())
+        .add_attribute("refund", escrow);
+
+    Ok(res.add_event(event))
+}
+
+pub fn try_accept_offer(
+    env: ExecuteEnv,
+    nft_collection: String,
+    nft_id: String,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { mut deps, info, env } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    nonpayable(&info)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Execute, &[&nft_collection])?;
+    let bidder = deps.api.addr_validate(&bidder)?;
+
+    let key = offer_key(&nft_collection, nft_id.clone(), &bidder);
+    let offer = offers().load(deps.storage, key.clone())?;
+
+    only_owner(deps.as_ref(), &info, &nft_collection, nft_id)?;
+
+    // the escrow already covers the price and the bidder's fee
+    let escrow = offer.escrow();
+    let mut trade = offer.into_trade(info.sender.clone());
+    apply_holder_discounts(deps.as_ref(), &mut trade)?;
+    let mut res = release_trade(&mut deps, &env, &trade, escrow)?;
+
+    offers().remove(deps.storage, key)?;
+    drop_conflicting_trades(deps.storage, &trade, &mut res)?;
+
+    Ok(res.add_attribute("method", "accept_offer"))
+}
+
+pub fn try_create_collection_offer(
+    env: ExecuteEnv,
+    cw20_payment: Option<Cw20CoinVerified>,
+    nft_collection: String,
+    price: TradePrice,
+    quantity: u32,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { deps, info, env: _ } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    price_validate(&price, &cfg)?;
+
+    if quantity == 0 {
+        return Err(ContractError::InvalidQuantity {});
+    }
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Create, &[&nft_collection])?;
+
+    let key = collection_offer_key(&nft_collection, &info.sender);
+    if collection_offers().has(deps.storage, key.clone()) {
+        return Err(ContractError::OfferAlreadyExists {});
+    }
+
+    let fees = collection_fees(deps.as_ref(), &cfg, &nft_collection)?;
+    let offer = CollectionOffer {
+        bidder: info.sender.clone(),
+        price: price.clone(),
+        nft_collection: nft_collection.clone(),
+        quantity,
+        seller_fee: fees.seller_fee,
+        buyer_fee: fees.buyer_fee,
+    };
+
+    // the bidder escrows the price plus their fee for every token wanted
+    let escrow = offer.escrow();
+    must_pay_escrow(&info, &cw20_payment, &price, escrow)?;
+
+    collection_offers().save(deps.storage, key, &offer)?;
+
+    let event = Event::new("create-collection-offer")
+        .add_attribute("bidder", info.sender.to_string())
+        .add_attribute("nft_collection", nft_collection.to_string())
+        .add_attribute("price", price.amount().to_string())
+        .add_attribute("denom", price.denom())
+        .add_attribute("quantity", quantity.to_string())
+        .add_attribute("escrow", escrow);
+
+    Ok(Response::new().add_event(event))
+}
+
+// the bidder can take back what is left of the escrow at any time, even with the emergency break on
+pub fn try_withdraw_collection_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_collection: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    let key = collection_offer_key(&nft_collection, &info.sender);
+    let offer = collection_offers().load(deps.storage, key.clone())?;
+
+    collection_offers().remove(deps.storage, key)?;
+
+    let escrow = offer.escrow();
+    let mut res = Response::new();
+    if !escrow.is_zero() {
+        let refund_msg = transfer_price_msg(&offer.bidder, &offer.price.with_amount(escrow))?;
+        res.messages.push(SubMsg::new(refund_msg));
+    }
+
+    let event = Event::new("bidder-withdraws-collection-offer")
+        .add_attribute("collection", nft_collection.to_string())
+        .add_attribute("bidder", offer.bidder.to_string())
+        .add_attribute("quantity", offer.quantity.to_string())
+        .add_attribute("refund", escrow);
+
+    Ok(res.add_event(event))
+}
+
+pub fn try_accept_collection_offer(
+    env: ExecuteEnv,
+    nft_collection: String,
+    nft_id: String,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    let ExecuteEnv { mut deps, info, env } = env;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    nonpayable(&info)?;
+
+    let nft_collection = deps.api.addr_validate(&nft_collection)?;
+    ensure_not_paused(deps.as_ref(), &cfg, PauseAction::Execute, &[&nft_collection])?;
+    let bidder = deps.api.addr_validate(&bidder)?;
+
+    let key = collection_offer_key(&nft_collection, &bidder);
+    let mut offer = collection_offers().load(deps.storage, key.clone())?;
+
+    // any holder of the collection may fill the offer, except the bidder
+    only_owner(deps.as_ref(), &info, &nft_collection, nft_id.clone())?;
+    if info.sender == offer.bidder {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut trade = offer.to_trade(info.sender.clone(), nft_id);
+    apply_holder_discounts(deps.as_ref(), &mut trade)?;
+    let mut res = release_trade(&mut deps, &env, &trade, offer.escrow_per_token())?;
+    drop_conflicting_trades(deps.storage, &trade, &mut res)?;
+
+    offer.quantity -= 1;
+    if offer.quantity == 0 {
+        collection_offers().remove(deps.storage, key)?;
+    } else {
+        collection_offers().save(deps.storage, key, &offer)?;
+    }
+
+    Ok(res
+        .add_attribute("method", "accept_collection_offer")
+        .add_attribute("remaining_quantity", offer.quantity.to_string()))
+}
+
+// check the funds sent match the escrow, native or CW20
+fn must_pay_escrow(
+    info: &MessageInfo,
+    cw20_payment: &Option<Cw20CoinVerified>,
+    price: &TradePrice,
+    escrow: Uint128,
+) -> Result<(), ContractError> {
+    if escrow.is_zero() {
+        nonpayable(info)?;
+        if cw20_payment.is_some() {
+            return Err(ContractError::PaymentAmountMismatch {});
+        }
+    } else if must_pay_price(info, cw20_payment, price)? != escrow {
+        return Err(ContractError::PaymentAmountMismatch {});
+    }
+
+    Ok(())
+}
+
+fn transfer_nft(trade: &Trade, res: &mut Response) -> StdResult<()> {
+    for nft in trade.seller_nfts().iter() {
+        res.messages.push(SubMsg::new(transfer_nft_msg(nft, &trade.buyer)?));
+    }
+
+    // swap: the buyer's NFTs go to the seller in the same transaction
+    for nft in trade.wanted_nfts.iter() {
+        res.messages.push(SubMsg::new(transfer_nft_msg(nft, &trade.seller)?));
+    }
+
+    let mut event = Event::new("finalize-switch")
+        .add_attribute("nft_collection", trade.nft_collection.to_string())
+        .add_attribute("nft_id", trade.nft_id.to_string())
+        .add_attribute("seller", trade.seller.to_string())
+        .add_attribute("buyer", trade.buyer.to_string())
+        .add_attribute("price", trade.price.to_string());
+
+    if !trade.offered_nfts.is_empty() || !trade.wanted_nfts.is_empty() {
+        event = event
+            .add_attribute("offered_nfts", trade.seller_nfts().len().to_string())
+            .add_attribute("wanted_nfts", trade.wanted_nfts.len().to_string())
+            .add_attribute("seller_coins", trade.seller_coins);
+    }
+
+    res.events.push(event);
+
+    Ok(())
+}
+
+// reserve the seller's NFTs for this trade, unless another live trade already holds one of them
+fn reserve_tokens(
+    storage: &mut dyn Storage,
+    env: &Env,
+    key: &TradeKey,
+    trade: &Trade,
+) -> Result<(), ContractError> {
+    for nft in trade.seller_nfts() {
+        let token = (nft.collection, nft.token_id);
+        if let Some(holder) = TOKEN_RESERVATIONS.may_load(storage, token.clone())? {
+            // a trade that expired no longer holds the token, even before it is pruned
+            if let Some(other) = trades().may_load(storage, holder)? {
+                if !other.expires_at.is_expired(&env.block) {
+                    return Err(ContractError::TokenReserved {});
+                }
+            }
+        }
+        TOKEN_RESERVATIONS.save(storage, token, key)?;
+    }
+    Ok(())
+}
+
+// drop the reservations a removed trade still holds
+fn release_tokens(storage: &mut dyn Storage, key: &TradeKey, trade: &Trade) -> StdResult<()> {
+    for nft in trade.seller_nfts() {
+        let token = (nft.collection, nft.token_id);
+        if TOKEN_RESERVATIONS.may_load(storage, token.clone())?.as_ref() == Some(key) {
+            TOKEN_RESERVATIONS.remove(storage, token);
+        }
+    }
+    Ok(())
+}
+
+// once NFTs change hands, any other trade or listing selling one of them can never settle
+fn drop_conflicting_trades(
+    storage: &mut dyn Storage,
+    settled: &Trade,
+    res: &mut Response,
+) -> StdResult<()> {
+    let cfg = CONFIG.load(storage)?;
+
+    for nft in settled.seller_nfts().iter().chain(settled.wanted_nfts.iter()) {
+        drop_stale_listing(storage, &cfg, nft, res)?;
+
+        // the reservation names the only live trade selling the token
+        let token = (nft.collection.clone(), nft.token_id.clone());
+        let key = match TOKEN_RESERVATIONS.may_load(storage, token)? {
+            Some(key) => key,
+            None => continue,
+        };
+        let trade = match trades().may_load(storage, key.clone())? {
+            Some(trade) => trade,
+            None => continue,
+        };
+
+        trades().remove(storage, key.clone())?;
+        release_tokens(storage, &key, &trade)?;
+        refund_seller(&trade, res)?;
+        // the seller parted with the NFT, so the fee is kept
+        resolve_listing_fee(&trade, &cfg.fee_admin, "forfeited", res)?;
+
+        res.events.push(
+            Event::new("drop-conflicting-trade")
+                .add_attribute("collection", trade.nft_collection.to_string())
+                .add_attribute("nft_id", trade.nft_id.to_string())
+                .add_attribute("seller", trade.seller.to_string())
+                .add_attribute("buyer", trade.buyer.to_string()),
+        );
+    }
+    Ok(())
+}
+
+// listings hold no reservation, so one left behind by the previous owner is dropped here
+fn drop_stale_listing(
+    storage: &mut dyn Storage,
+    cfg: &Config,
+    nft: &NftItem,
+    res: &mut Response,
+) -> StdResult<()> {
+    let key = listing_key(&nft.collection, nft.token_id.clone());
+    let listing = match listings().may_load(storage, key.clone())? {
+        Some(listing) => listing,
+        None => return Ok(()),
+    };
+
+    listings().remove(storage, key)?;
+    // as with trades, the seller parted with the NFT, so the fee is kept
+    resolve_unsold_listing_fee(&listing, &cfg.fee_admin, "forfeited", res)?;
+
+    res.events.push(
+        Event::new("drop-stale-listing")
+            .add_attribute("collection", nft.collection.to_string())
+            .add_attribute("nft_id", nft.token_id.to_string())
+            .add_attribute("seller", listing.seller.to_string()),
+    );
+    Ok(())
+}
+
+// give back what the seller escrowed: coins added to a swap and NFTs held in custody
+fn refund_seller(trade: &Trade, res: &mut Response) -> StdResult<()> {
+    if !trade.seller_coins.is_zero() {
+        let refund_msg =
+            transfer_price_msg(&trade.seller, &trade.price.with_amount(trade.seller_coins))?;
+        res.messages.push(SubMsg::new(refund_msg));
+    }
+    if trade.nft_in_escrow {
+        for nft in trade.seller_nfts().iter() {
+            res.messages.push(SubMsg::new(transfer_nft_msg(nft, &trade.seller)?));
+        }
+    }
+    Ok(())
+}
+
+// the listing fee held for a trade goes back to the seller or on to the fee admin
+fn resolve_listing_fee(
+    trade: &Trade,
+    recipient: &Addr,
+    outcome: &str,
+    res: &mut Response,
+) -> StdResult<()> {
+    if trade.listing_fee.is_zero() {
+        return Ok(());
+    }
+
+    let listing_fee_msg = transfer_price_msg(recipient, &trade.price.with_amount(trade.listing_fee))?;
+    res.messages.push(SubMsg::new(listing_fee_msg));
+    res.events.push(
+        Event::new("listing-fee")
+            .add_attribute("outcome", outcome)
+            .add_attribute("recipient", recipient.to_string())
+            .add_attribute("amount", trade.listing_fee)
+            .add_attribute("denom", trade.price.denom())
+            .add_attribute("collection", trade.nft_collection.to_string())
+            .add_attribute("nft_id", trade.nft_id.to_string()),
+    );
+    Ok(())
+}
+
+// a listing that never sold settles its fee the way an unsettled trade does
+fn resolve_unsold_listing_fee(
+    listing: &Listing,
+    recipient: &Addr,
+    outcome: &str,
+    res: &mut Response,
+) -> StdResult<()> {
+    let trade = listing.clone().into_trade(listing.seller.clone());
+    resolve_listing_fee(&trade, recipient, outcome, res)
+}
+
+// send amount to seller
+fn transfer_coin_to_seller(trade: &Trade, amount: Uint128, res: &mut Response) -> StdResult<()> {
+    let transfer_amount = trade.price.with_amount(amount);
+
+    let seller_transfer_msg = transfer_price_msg(&trade.seller, &transfer_amount)?;
+    res.messages.push(SubMsg::new(seller_transfer_msg));
+    Ok(())
+}
+
+// send commission
+fn transfer_commission(deps: &DepsMut, commission: TradePrice, res: &mut Response) -> StdResult<()> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // the disburse contract only splits native funds, CW20 commission is transferred as is
+    let commission = match commission {
+        TradePrice::Native(commission) => commission,
+        TradePrice::Cw20(_) => {
+            let transfer_commission_msg = transfer_price_msg(&cfg.commission_addr, &commission)?;
+            res.messages.push(SubMsg::new(transfer_commission_msg));
+            return Ok(());
+        }
+    };
 
-    let disburse_reward_msg = DisburseReward {
-        amount : commission.clone()
+    let disburse_reward_msg = DisburseExecuteMsg::DisburseReward {
+        amount: commission.amount,
+        denom: commission.denom.clone(),
     };
     let exec_disburse_reward = WasmMsg::Execute {
         contract_addr: cfg.commission_addr.to_string(),
         msg: to_binary(&disburse_reward_msg)?,
-        funds: vec![coin(commission.u128(), NATIVE_DENOM)],
+        funds: vec![commission],
     };
     res.messages.push(SubMsg::new(exec_disburse_reward));
 
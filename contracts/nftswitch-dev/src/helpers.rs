@@ -1,10 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg, WasmQuery, Coin, Deps, QueryRequest, MessageInfo};
-use cw721::{Cw721QueryMsg, OwnerOfResponse, TokensResponse};
+use cosmwasm_std::{to_binary, to_vec, Addr, BankMsg, Binary, CosmosMsg, StdResult, WasmMsg, WasmQuery, Coin, Decimal, Deps, Env, Order, QueryRequest, MessageInfo, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse, TokensResponse};
 use cw721_base::helpers::Cw721Contract;
+use cw_utils::{must_pay, nonpayable, Expiration, PaymentError};
+use sha2::{Digest, Sha256};
 use crate::ContractError;
-use crate::msg::{ExecuteMsg};
+use crate::msg::{ExecuteMsg, FeeConfirmation, SignedFeeConfirmation};
+use crate::state::{
+    Config, FeeBounds, FeeDiscount, FeeSchedule, NftItem, PauseAction, Role, Trade, TradePrice, COLLECTION_FEES,
+    COLLECTION_PAUSES, FEE_CONFIRMATION_NONCES, HOLDER_DISCOUNTS, MAX_HOLDER_DISCOUNTS, OPERATORS, ROLES,
+};
 
 /// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
@@ -27,14 +34,114 @@ impl CwTemplateContract {
     }
 }
 
-pub fn price_validate(price: &Coin) -> Result<(), ContractError> {
-    if price.denom != "uluna" {
+pub fn price_validate(price: &TradePrice, cfg: &Config) -> Result<(), ContractError> {
+    let accepted = match price {
+        TradePrice::Native(coin) => cfg.listing_fees.iter().any(|fee| fee.denom == coin.denom),
+        TradePrice::Cw20(coin) => cfg
+            .cw20_listing_fees
+            .iter()
+            .any(|fee| fee.address == coin.address),
+    };
+    if !accepted {
         return Err(ContractError::InvalidPrice {});
     }
 
     Ok(())
 }
 
+// Look up the listing fee charged for trades priced in the same asset as the given price.
+pub fn listing_fee_for(cfg: &Config, price: &TradePrice) -> Result<TradePrice, ContractError> {
+    let listing_fee = match price {
+        TradePrice::Native(coin) => cfg
+            .listing_fees
+            .iter()
+            .find(|fee| fee.denom == coin.denom)
+            .map(|fee| TradePrice::Native(fee.clone())),
+        TradePrice::Cw20(coin) => cfg
+            .cw20_listing_fees
+            .iter()
+            .find(|fee| fee.address == coin.address)
+            .map(|fee| TradePrice::Cw20(fee.clone())),
+    };
+
+    listing_fee.ok_or(ContractError::InvalidDenom {})
+}
+
+// Every accepted denom may only appear once in the registry.
+pub fn listing_fees_validate(listing_fees: &[Coin]) -> Result<(), ContractError> {
+    for (i, fee) in listing_fees.iter().enumerate() {
+        if fee.denom.is_empty() || listing_fees[..i].iter().any(|other| other.denom == fee.denom) {
+            return Err(ContractError::InvalidDenom {});
+        }
+    }
+
+    Ok(())
+}
+
+// Every accepted CW20 token may only appear once in the registry.
+pub fn cw20_listing_fees_validate(
+    deps: Deps,
+    cw20_listing_fees: Vec<Cw20Coin>,
+) -> Result<Vec<Cw20CoinVerified>, ContractError> {
+    let mut verified: Vec<Cw20CoinVerified> = vec![];
+    for fee in cw20_listing_fees {
+        let address = deps.api.addr_validate(&fee.address)?;
+        if verified.iter().any(|other| other.address == address) {
+            return Err(ContractError::InvalidDenom {});
+        }
+        verified.push(Cw20CoinVerified {
+            address,
+            amount: fee.amount,
+        });
+    }
+
+    Ok(verified)
+}
+
+// Amount paid in the asset of the given price, sent either as native funds or through a CW20 Receive.
+pub fn must_pay_price(
+    info: &MessageInfo,
+    cw20_payment: &Option<Cw20CoinVerified>,
+    price: &TradePrice,
+) -> Result<Uint128, ContractError> {
+    match price {
+        TradePrice::Native(coin) => {
+            if cw20_payment.is_some() {
+                return Err(ContractError::InvalidDenom {});
+            }
+            Ok(must_pay(info, &coin.denom)?)
+        }
+        TradePrice::Cw20(coin) => {
+            nonpayable(info)?;
+            match cw20_payment {
+                Some(payment) if payment.address == coin.address => Ok(payment.amount),
+                Some(_) => Err(ContractError::InvalidDenom {}),
+                None => Err(ContractError::TradePaymentError(PaymentError::NoFunds {})),
+            }
+        }
+    }
+}
+
+// Message sending the given amount to the recipient, as a bank send or a CW20 transfer.
+pub fn transfer_price_msg(recipient: &Addr, amount: &TradePrice) -> StdResult<CosmosMsg> {
+    match amount {
+        TradePrice::Native(coin) => Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin.clone()],
+        }
+        .into()),
+        TradePrice::Cw20(coin) => Ok(WasmMsg::Execute {
+            contract_addr: coin.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: coin.amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
 pub fn only_owner(
     deps: Deps,
     info: &MessageInfo,
@@ -49,6 +156,155 @@ pub fn only_owner(
     Ok(res)
 }
 
+pub fn fee_caps_validate(
+    bounds: &FeeBounds,
+    buyer_fee: Option<Decimal>,
+    seller_fee: Option<Decimal>,
+) -> Result<(), ContractError> {
+    if let Some(fee) = buyer_fee.filter(|fee| *fee > bounds.max_buyer_fee) {
+        return Err(ContractError::BuyerFeeAboveCap {
+            fee,
+            max: bounds.max_buyer_fee,
+        });
+    }
+
+    if let Some(fee) = seller_fee.filter(|fee| *fee > bounds.max_seller_fee) {
+        return Err(ContractError::SellerFeeAboveCap {
+            fee,
+            max: bounds.max_seller_fee,
+        });
+    }
+
+    Ok(())
+}
+
+// The admin holds every role and the fee admin confirms trades, on top of the granted members.
+pub fn has_role(deps: Deps, cfg: &Config, role: Role, addr: &Addr) -> StdResult<bool> {
+    if *addr == cfg.admin || (role == Role::TradeConfirmer && *addr == cfg.fee_admin) {
+        return Ok(true);
+    }
+
+    Ok(ROLES.has(deps.storage, (role.as_str().to_string(), addr.clone())))
+}
+
+pub fn only_role(deps: Deps, cfg: &Config, role: Role, addr: &Addr) -> Result<(), ContractError> {
+    if !has_role(deps, cfg, role, addr)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+// The emergency break halts everything but cancellations, which only their own switch stops.
+pub fn ensure_not_paused(
+    deps: Deps,
+    cfg: &Config,
+    action: PauseAction,
+    collections: &[&Addr],
+) -> Result<(), ContractError> {
+    if cfg.e_break && action != PauseAction::Cancel {
+        return Err(ContractError::EmergencyBreakActivated {});
+    }
+
+    if cfg.pauses.is_paused(action) {
+        return Err(ContractError::ActionPaused {
+            action: action.as_str().to_string(),
+        });
+    }
+
+    for collection in collections {
+        let paused = COLLECTION_PAUSES
+            .may_load(deps.storage, (*collection).clone())?
+            .map_or(false, |pauses| pauses.is_paused(action));
+        if paused {
+            return Err(ContractError::CollectionPaused {
+                collection: collection.to_string(),
+                action: action.as_str().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// The sender acts for `principal`, either as the principal itself or as one of its operators that
+// has not expired and may act on `collection`.
+pub fn only_principal_or_operator(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    principal: &Addr,
+    collections: &[&Addr],
+) -> Result<(), ContractError> {
+    if sender == principal {
+        return Ok(());
+    }
+
+    let operator = OPERATORS
+        .may_load(deps.storage, (principal.clone(), sender.clone()))?
+        .ok_or(ContractError::Unauthorized {})?;
+    if operator.expires_at.is_expired(&env.block)
+        || (!operator.collections.is_empty()
+            && collections
+                .iter()
+                .any(|collection| !operator.collections.contains(collection)))
+    {
+        return Err(ContractError::UnauthorizedOperator {});
+    }
+
+    Ok(())
+}
+
+// Check that the owner still holds the NFT and has approved the spender to transfer it.
+pub fn check_nft_approval(
+    deps: Deps,
+    nft: &NftItem,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<(), ContractError> {
+    let contract = Cw721Contract(nft.collection.clone());
+    let res = contract.owner_of(&deps.querier, nft.token_id.clone(), false)?;
+    if &res.owner != owner {
+        return Err(ContractError::UnauthorizedOwner {});
+    }
+
+    contract
+        .approval(&deps.querier, nft.token_id.clone(), spender.to_string(), None)
+        .map_err(|_| ContractError::NeedsApproval {})?;
+
+    Ok(())
+}
+
+// Validate the collection addresses of NFTs sent in a message.
+pub fn nfts_validate(deps: Deps, nfts: Vec<NftItem>) -> Result<Vec<NftItem>, ContractError> {
+    let mut validated: Vec<NftItem> = vec![];
+    for nft in nfts {
+        let nft = NftItem {
+            collection: deps.api.addr_validate(nft.collection.as_str())?,
+            token_id: nft.token_id,
+        };
+        if validated.contains(&nft) {
+            return Err(ContractError::DuplicateNft {});
+        }
+        validated.push(nft);
+    }
+
+    Ok(validated)
+}
+
+// Message transferring the NFT to the recipient, the contract acting as an approved spender.
+pub fn transfer_nft_msg(nft: &NftItem, recipient: &Addr) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft.collection.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: nft.token_id.clone(),
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
 // Query a wallet to see which NFTs it holds.
 // This returns "tokens" which is a vector of all the token ids that wallet holds.
 // Tokens{owner, start_after, limit} - List all token_ids that belong to a given owner.
@@ -65,5 +321,162 @@ pub fn addr_owns_collection(deps: Deps, wallet: Addr, collection: &Addr) -> Resu
             })?,
         }))?;
 
-    Ok(query_response.tokens.len() > 0)
-}
\ No newline at end of file
+    Ok(!query_response.tokens.is_empty())
+}
+
+// Fees for new trades in the collection: its override if set, otherwise the global fees.
+pub fn collection_fees(deps: Deps, cfg: &Config, collection: &Addr) -> StdResult<FeeSchedule> {
+    let fees = COLLECTION_FEES
+        .may_load(deps.storage, collection.clone())?
+        .unwrap_or(FeeSchedule {
+            buyer_fee: cfg.buyer_fee,
+            seller_fee: cfg.seller_fee,
+            partner: false,
+        });
+
+    Ok(fees)
+}
+
+// Best buyer and seller discount among the membership collections the wallet holds tokens of.
+// Collections whose holder query fails are skipped rather than failing the settlement.
+pub fn holder_discount(deps: Deps, wallet: &Addr) -> Result<FeeDiscount, ContractError> {
+    let memberships = HOLDER_DISCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(MAX_HOLDER_DISCOUNTS as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut best = FeeDiscount::default();
+    for (collection, discount) in memberships {
+        // a membership collection that fails to answer grants nothing rather than blocking trades
+        if addr_owns_collection(deps, wallet.clone(), &collection).unwrap_or(false) {
+            best.buyer_discount = best.buyer_discount.max(discount.buyer_discount);
+            best.seller_discount = best.seller_discount.max(discount.seller_discount);
+        }
+    }
+
+    Ok(best)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum Cw2981QueryMsg {
+    RoyaltyInfo { token_id: String, sale_price: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum Cw2981ExtensionQueryMsg {
+    Extension { msg: Cw2981QueryMsg },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+// Ask a cw2981 collection what royalty is owed on a sale of the token at the given price.
+pub fn query_royalty(
+    deps: Deps,
+    collection: &Addr,
+    token_id: String,
+    sale_price: Uint128,
+) -> StdResult<RoyaltiesInfoResponse> {
+    deps.querier.query_wasm_smart(
+        collection,
+        &Cw2981ExtensionQueryMsg::Extension {
+            msg: Cw2981QueryMsg::RoyaltyInfo {
+                token_id,
+                sale_price,
+            },
+        },
+    )
+}
+
+// Compressed (33 bytes) or uncompressed (65 bytes) secp256k1 public key.
+pub fn pubkey_validate(pubkey: &Binary) -> Result<(), ContractError> {
+    match pubkey.len() {
+        33 if pubkey[0] == 0x02 || pubkey[0] == 0x03 => Ok(()),
+        65 if pubkey[0] == 0x04 => Ok(()),
+        _ => Err(ContractError::InvalidPubkey {}),
+    }
+}
+
+#[derive(Serialize)]
+struct FeeConfirmationPayload<'a> {
+    contract: &'a Addr,
+    seller: &'a Addr,
+    buyer: &'a Addr,
+    nft_collection: &'a Addr,
+    nft_id: &'a str,
+    offered_nfts: &'a [NftItem],
+    wanted_nfts: &'a [NftItem],
+    price: &'a TradePrice,
+    seller_coins: Uint128,
+    trade_expires_at: &'a Expiration,
+    nft_in_escrow: bool,
+    seller_fee_pct: Decimal,
+    buyer_fee_pct: Decimal,
+    expires_at: &'a Expiration,
+    nonce: u64,
+}
+
+// Bytes the fee admin signs: the JSON of this contract's address, the trade's terms and the
+// confirmation, hashed with SHA-256 before signing. A signature can not be replayed on another
+// deployment or on a trade recreated under the same key with other terms.
+pub fn fee_confirmation_payload(
+    contract: &Addr,
+    trade: &Trade,
+    confirmation: &FeeConfirmation,
+) -> StdResult<Vec<u8>> {
+    to_vec(&FeeConfirmationPayload {
+        contract,
+        seller: &trade.seller,
+        buyer: &trade.buyer,
+        nft_collection: &trade.nft_collection,
+        nft_id: &trade.nft_id,
+        offered_nfts: &trade.offered_nfts,
+        wanted_nfts: &trade.wanted_nfts,
+        price: &trade.price,
+        seller_coins: trade.seller_coins,
+        trade_expires_at: &trade.expires_at,
+        nft_in_escrow: trade.nft_in_escrow,
+        seller_fee_pct: confirmation.seller_fee_pct,
+        buyer_fee_pct: confirmation.buyer_fee_pct,
+        expires_at: &confirmation.expires_at,
+        nonce: confirmation.nonce,
+    })
+}
+
+// Check a signed fee confirmation against the fee admin's public key. The caller spends the nonce.
+pub fn verify_fee_confirmation(
+    deps: Deps,
+    env: &Env,
+    cfg: &Config,
+    trade: &Trade,
+    signed: &SignedFeeConfirmation,
+) -> Result<(), ContractError> {
+    let pubkey = cfg
+        .fee_admin_pubkey
+        .as_ref()
+        .ok_or(ContractError::MissingFeeAdminPubkey {})?;
+
+    let confirmation = &signed.confirmation;
+    if confirmation.expires_at.is_expired(&env.block) {
+        return Err(ContractError::FeeConfirmationExpired {});
+    }
+    if FEE_CONFIRMATION_NONCES.has(deps.storage, confirmation.nonce) {
+        return Err(ContractError::NonceAlreadyUsed {});
+    }
+
+    let hash = Sha256::digest(fee_confirmation_payload(&env.contract.address, trade, confirmation)?);
+    let verified = deps
+        .api
+        .secp256k1_verify(&hash, &signed.signature, pubkey)
+        .map_err(|_| ContractError::InvalidFeeSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidFeeSignature {});
+    }
+
+    Ok(())
+}
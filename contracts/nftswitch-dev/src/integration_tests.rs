@@ -1,15 +1,34 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        msg::{ConfigResponse, ExecuteMsg, QueryMsg, TradeResponse, TradesResponse},
+        helpers::{fee_confirmation_payload, RoyaltiesInfoResponse},
+        msg::{
+            CollectionFeesResponse, CollectionOfferResponse, HolderDiscountResponse, CollectionOffersResponse, ConfigResponse, ExecuteMsg,
+            CollectionPauses, FeeConfirmation, PendingCollectionFeeChangeResponse, PendingFeeChangeResponse, HasRoleResponse, ListingOffset, PauseMatrixResponse, ListingResponse, ListingsResponse, OfferResponse, OffersResponse,
+            OperatorsResponse, QueryMsg, ReceiveMsg, RoleMembersResponse, RoyaltyPolicyResponse, SaleResponse,
+            SalesResponse, StatsResponse, SignedFeeConfirmation,
+            TradeFilter, TradeOffset, TradeResponse, TradesResponse,
+        },
+        state::{trade_key, FeeBounds, FeeDiscount, FeeSchedule, NftItem, PauseAction, Pauses, Role, RoyaltyPolicy, TradePrice, TradeStats},
         ContractError,
     };
-    use cosmwasm_std::{coin, coins, Addr, Coin, Decimal, Empty, Uint128};
+    use cosmwasm_std::{
+        coin, coins, to_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+        MessageInfo, Response, StdError, StdResult, Uint128,
+    };
+    use cw2::ContractVersion;
+    use cw20::{BalanceResponse, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
     use cw721::{Cw721QueryMsg, OwnerOfResponse};
     use cw721_base::{
         ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, MintMsg,
     };
     use cw_multi_test::{App, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+    use cw_utils::Expiration;
+    use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use disbursement::state::Payees;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
 
     fn custom_mock_app() -> App {
         App::default()
@@ -20,6 +39,70 @@ mod tests {
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
+        )
+        .with_migrate(crate::contract::migrate);
+        Box::new(contract)
+    }
+
+    pub fn contract_disburse() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            disbursement::contract::execute,
+            disbursement::contract::instantiate,
+            disbursement::contract::query,
+        );
+        Box::new(contract)
+    }
+
+    pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        );
+        Box::new(contract)
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum RoyaltyInfoQuery {
+        RoyaltyInfo { token_id: String, sale_price: Uint128 },
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum RoyaltyExtensionQuery {
+        Extension { msg: RoyaltyInfoQuery },
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(untagged)]
+    enum RoyaltyCollectionQuery {
+        Royalty(RoyaltyExtensionQuery),
+        Base(cw721_base::QueryMsg),
+    }
+
+    // cw721 collection paying a 5% royalty to "creator" on every token
+    fn royalty_collection_query(
+        deps: Deps,
+        env: Env,
+        msg: RoyaltyCollectionQuery,
+    ) -> StdResult<Binary> {
+        match msg {
+            RoyaltyCollectionQuery::Royalty(RoyaltyExtensionQuery::Extension {
+                msg: RoyaltyInfoQuery::RoyaltyInfo { sale_price, .. },
+            }) => to_binary(&RoyaltiesInfoResponse {
+                address: "creator".to_string(),
+                royalty_amount: sale_price * Decimal::percent(5),
+            }),
+            RoyaltyCollectionQuery::Base(msg) => cw721_base::entry::query(deps, env, msg),
+        }
+    }
+
+    pub fn contract_royalty_nft() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw721_base::entry::execute,
+            cw721_base::entry::instantiate,
+            royalty_collection_query,
         );
         Box::new(contract)
     }
@@ -39,6 +122,8 @@ mod tests {
 
     const NATIVE_DENOM: &str = "uluna";
 
+    const SECONDARY_DENOM: &str = "uusd";
+
     fn setup_contract(router: &mut App, creator: &Addr) -> Result<(Addr, Addr), ContractError> {
         let cw_template_id = router.store_code(contract_trade());
 
@@ -48,7 +133,9 @@ mod tests {
             commission_addr: "commission_addr".to_string(),
             buyer_fee: Decimal::from_ratio(15u128, 1000u128),
             seller_fee: Decimal::from_ratio(15u128, 1000u128),
-            listing_fee: Uint128::from(10u32),
+            listing_fees: vec![coin(10, NATIVE_DENOM), coin(5, SECONDARY_DENOM)],
+            cw20_listing_fees: vec![],
+            fee_admin_pubkey: None,
         };
 
         let trade = router
@@ -82,6 +169,73 @@ mod tests {
         Ok((trade, collection))
     }
 
+    // Route commission through a disburse contract that pays everything to "commission_addr"
+    fn setup_disburse(router: &mut App, creator: &Addr, trade_contract: &Addr) -> Addr {
+        let disburse_id = router.store_code(contract_disburse());
+
+        let msg = disbursement::msg::InstantiateMsg {
+            nft_switch_address: trade_contract.to_string(),
+            payees: vec![Payees {
+                payee_address: Addr::unchecked("commission_addr"),
+                percent_paid: Decimal::one(),
+                claimable_amount: Uint128::zero(),
+            }],
+        };
+
+        let disburse = router
+            .instantiate_contract(disburse_id, creator.clone(), &msg, &[], "disburse", None)
+            .unwrap();
+
+        let update_config_msg = ExecuteMsg::UpdateConfig {
+            commission_addr: Some(disburse.to_string()),
+            buyer_fee: None,
+            seller_fee: None,
+            listing_fees: None,
+            cw20_listing_fees: None,
+            fee_admin_pubkey: None,
+            e_break: None,
+        };
+        let res = router.execute_contract(
+            creator.clone(),
+            trade_contract.clone(),
+            &update_config_msg,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        disburse
+    }
+
+    fn setup_cw20(router: &mut App, creator: &Addr, initial_balances: Vec<Cw20Coin>) -> Addr {
+        let cw20_id = router.store_code(contract_cw20());
+
+        let msg = cw20_base::msg::InstantiateMsg {
+            name: String::from("Stable coin"),
+            symbol: String::from("STBL"),
+            decimals: 6,
+            initial_balances,
+            mint: None,
+            marketing: None,
+        };
+
+        router
+            .instantiate_contract(cw20_id, creator.clone(), &msg, &[], "cw20", None)
+            .unwrap()
+    }
+
+    fn cw20_balance(router: &App, token: &Addr, address: &Addr) -> Uint128 {
+        let res: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
     fn setup_accounts(router: &mut App) -> Result<(Addr, Addr, Addr), ContractError> {
         let admin: Addr = Addr::unchecked("admin");
         let seller: Addr = Addr::unchecked("seller");
@@ -160,6 +314,18 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    fn owner_of(router: &App, collection: &Addr, token_id: u32) -> String {
+        let query_owner_msg = Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        };
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(collection.clone(), &query_owner_msg)
+            .unwrap();
+        res.owner
+    }
+
     #[test]
     fn try_create_and_execute_trade() {
         let mut router = custom_mock_app();
@@ -169,6 +335,7 @@ mod tests {
 
         // Instantiate and configure contracts
         let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
 
         // Mint NFT for seller
         mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
@@ -180,7 +347,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(1000, WRONG_DENOM),
+            sale_price: coin(1000, WRONG_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res =
             router.execute_contract(seller.clone(), trade_contract.clone(), &create_trade, &[]);
@@ -192,7 +362,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: WRONG_TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(1000, NATIVE_DENOM),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res =
             router.execute_contract(seller.clone(), trade_contract.clone(), &create_trade, &[]);
@@ -203,7 +376,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(1000, NATIVE_DENOM),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -226,7 +402,7 @@ mod tests {
 
         assert_eq!(res.trade.clone().unwrap().buyer, buyer);
         assert_eq!(res.trade.clone().unwrap().seller, seller);
-        assert_eq!(res.trade.clone().unwrap().price, coin(1000, NATIVE_DENOM));
+        assert_eq!(res.trade.clone().unwrap().price, coin(1000, NATIVE_DENOM).into());
         assert_eq!(res.trade.clone().unwrap().nft_collection, collection);
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
@@ -252,6 +428,7 @@ mod tests {
             buyer: buyer.to_string(),
             nft_collection: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
         };
 
         // Should error with create trade wrong amount. Expected amount 1000(price) + 15(fee) = 1015
@@ -324,7 +501,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(0, NATIVE_DENOM),
+            sale_price: coin(0, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -349,7 +529,7 @@ mod tests {
 
         assert_eq!(res.trade.clone().unwrap().buyer, buyer);
         assert_eq!(res.trade.clone().unwrap().seller, seller);
-        assert_eq!(res.trade.clone().unwrap().price, coin(0, NATIVE_DENOM));
+        assert_eq!(res.trade.clone().unwrap().price, coin(0, NATIVE_DENOM).into());
         assert_eq!(res.trade.clone().unwrap().nft_collection, collection);
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
@@ -376,6 +556,7 @@ mod tests {
             buyer: buyer.to_string(),
             nft_collection: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
         };
 
         //Execute trade succesfully
@@ -441,7 +622,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(1000, NATIVE_DENOM),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -464,11 +648,23 @@ mod tests {
 
         assert_eq!(res.trade.clone().unwrap().buyer, buyer);
         assert_eq!(res.trade.clone().unwrap().seller, seller);
-        assert_eq!(res.trade.clone().unwrap().price, coin(1000, NATIVE_DENOM));
+        assert_eq!(res.trade.clone().unwrap().price, coin(1000, NATIVE_DENOM).into());
         assert_eq!(res.trade.clone().unwrap().nft_collection, collection);
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
         assert_eq!(res.trade.clone().unwrap().nft_id, TOKEN_ID.to_string());
 
+        // Should error when the named seller does not sell the token
+        let cancel_as_seller = ExecuteMsg::CancelTrade {
+            buyer: None,
+            seller: Some(buyer.to_string()),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_as_seller, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Std(StdError::NotFound { .. })));
+
         //cancel trade
         let execute_cancel_trade = ExecuteMsg::CancelTrade {
             buyer: Some(buyer.to_string()),
@@ -506,24 +702,21 @@ mod tests {
         assert_eq!(res.admin, admin);
         assert_eq!(res.fee_admin, Addr::unchecked("fee_admin"));
         assert_eq!(res.commission_addr, Addr::unchecked("commission_addr"));
-        assert_eq!(res.e_break, false);
+        assert!(!res.e_break);
         assert_eq!(res.buyer_fee, Decimal::from_ratio(15u128, 1000u128));
         assert_eq!(res.seller_fee, Decimal::from_ratio(15u128, 1000u128));
 
         //update admin
-        let update_config_msg = ExecuteMsg::UpdateConfig {
-            admin: Some("new_admin".to_string()),
-            fee_admin: None,
-            commission_addr: None,
-            buyer_fee: None,
-            seller_fee: None,
-            listing_fee: None,
-            e_break: None,
+        let propose_msg = ExecuteMsg::ProposeAdmin {
+            new_admin: "new_admin".to_string(),
+            expires_at: None,
         };
+        let res = router.execute_contract(admin.clone(), trade_contract.clone(), &propose_msg, &[]);
+        assert!(res.is_ok());
         let res = router.execute_contract(
-            admin.clone(),
+            Addr::unchecked("new_admin"),
             trade_contract.clone(),
-            &update_config_msg,
+            &ExecuteMsg::AcceptAdmin {},
             &[],
         );
         assert!(res.is_ok());
@@ -533,14 +726,34 @@ mod tests {
             .unwrap();
         assert_eq!(res.admin, Addr::unchecked("new_admin"));
 
+        //update fee admin
+        let propose_msg = ExecuteMsg::ProposeFeeAdmin {
+            new_fee_admin: "new_fee_admin".to_string(),
+            expires_at: None,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("new_admin"),
+            trade_contract.clone(),
+            &propose_msg,
+            &[],
+        );
+        assert!(res.is_ok());
+        let res = router.execute_contract(
+            Addr::unchecked("new_fee_admin"),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptFeeAdmin {},
+            &[],
+        );
+        assert!(res.is_ok());
+
         //update other option
         let update_config_msg = ExecuteMsg::UpdateConfig {
-            admin: None,
-            fee_admin: Some("new_fee_admin".to_string()),
             commission_addr: Some("new_commission_addr".to_string()),
             buyer_fee: Some(Decimal::from_ratio(2u128, 100u128)),
             seller_fee: Some(Decimal::from_ratio(2u128, 100u128)),
-            listing_fee: Some(Uint128::from(100000u32)),
+            listing_fees: Some(vec![coin(100000, NATIVE_DENOM)]),
+            cw20_listing_fees: None,
+            fee_admin_pubkey: None,
             e_break: Some(true),
         };
 
@@ -558,10 +771,25 @@ mod tests {
         assert_eq!(res.admin, Addr::unchecked("new_admin"));
         assert_eq!(res.fee_admin, Addr::unchecked("new_fee_admin"));
         assert_eq!(res.commission_addr, Addr::unchecked("new_commission_addr"));
-        assert_eq!(res.e_break, true);
+        assert!(res.e_break);
+        assert_eq!(res.listing_fees, vec![coin(100000, NATIVE_DENOM)]);
+
+        // fee increases wait out the timelock
+        assert_eq!(res.buyer_fee, Decimal::from_ratio(15u128, 1000u128));
+        router.update_block(|block| block.time = block.time.plus_seconds(86400));
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::ApplyFeeChange {},
+            &[],
+        );
+        assert!(res.is_ok());
+        let res: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &config_msg)
+            .unwrap();
         assert_eq!(res.buyer_fee, Decimal::from_ratio(2u128, 100u128));
         assert_eq!(res.seller_fee, Decimal::from_ratio(2u128, 100u128));
-        assert_eq!(res.listing_fee, Uint128::from(100000u32));
     }
 
     #[test]
@@ -599,7 +827,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(100, NATIVE_DENOM),
+            sale_price: coin(100, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -614,7 +845,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: (TOKEN_ID + 1).to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(110, NATIVE_DENOM),
+            sale_price: coin(110, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -629,7 +863,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: (TOKEN_ID + 2).to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(120, NATIVE_DENOM),
+            sale_price: coin(120, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -642,6 +879,8 @@ mod tests {
         let query_trade_by_seller_msg = QueryMsg::GetTradesBySeller {
             seller: seller.clone(),
             limit: None,
+            include_expired: None,
+            start_after: None,
         };
         let res: TradesResponse = router
             .wrap()
@@ -699,7 +938,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: TOKEN_ID.to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(100, NATIVE_DENOM),
+            sale_price: coin(100, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -714,7 +956,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: (TOKEN_ID + 1).to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(110, NATIVE_DENOM),
+            sale_price: coin(110, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller.clone(),
@@ -729,7 +974,10 @@ mod tests {
             nft_addr: collection.to_string(),
             nft_id: (TOKEN_ID + 2).to_string(),
             buyer_addr: buyer.to_string(),
-            sale_price: coin(120, NATIVE_DENOM),
+            sale_price: coin(120, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
         };
         let res = router.execute_contract(
             seller_2.clone(),
@@ -743,6 +991,8 @@ mod tests {
         let query_trade_by_buyer_msg = QueryMsg::GetTradesByBuyer {
             buyer: buyer.clone(),
             limit: None,
+            include_expired: None,
+            start_after: None,
         };
         let res: TradesResponse = router
             .wrap()
@@ -750,4 +1000,4137 @@ mod tests {
             .unwrap();
         assert_eq!(res.trades.len(), 3usize);
     }
+
+    #[test]
+    fn try_create_and_execute_trade_in_secondary_denom() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+        for addr in [&seller, &buyer] {
+            router
+                .sudo(SudoMsg::Bank({
+                    BankSudo::Mint {
+                        to_address: addr.to_string(),
+                        amount: coins(500, SECONDARY_DENOM),
+                    }
+                }))
+                .map_err(|err| println!("{:?}", err))
+                .ok();
+        }
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Mint NFT for seller
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        // Should error when the listing fee is paid in another denom than the price
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(200, SECONDARY_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Create trade successfully
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(5, SECONDARY_DENOM),
+        );
+        assert!(res.is_ok());
+
+        //confirm by fee admin
+        let execute_confirm_by_fee_admin = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &execute_confirm_by_fee_admin,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+
+        // Should error when paying in another denom than the trade price
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(203, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Execute trade successfully. Expected amount 200(price) + 3(fee) = 203
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(203, SECONDARY_DENOM),
+        );
+        assert!(res.is_ok());
+
+        //seller get 197 (203 - 3(seller_fee) - 3(buyer_fee)) and paid 5 listing fee
+        let seller_balance = router
+            .wrap()
+            .query_balance(seller.clone(), SECONDARY_DENOM)
+            .unwrap();
+        assert_eq!(seller_balance, coin(692, SECONDARY_DENOM));
+
+        //commission addr get 3 fee from seller and 3 fee from buyer = 6 coin
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(6, SECONDARY_DENOM));
+
+        //fee admin get 5 listing fee
+        let fee_admin_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("fee_admin"))
+            .unwrap();
+        assert_eq!(fee_admin_bal, coins(5, SECONDARY_DENOM));
+    }
+
+    #[test]
+    fn try_create_and_execute_trade_with_cw20() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        let token = setup_cw20(
+            &mut router,
+            &admin,
+            vec![
+                Cw20Coin {
+                    address: seller.to_string(),
+                    amount: Uint128::from(500u128),
+                },
+                Cw20Coin {
+                    address: buyer.to_string(),
+                    amount: Uint128::from(500u128),
+                },
+            ],
+        );
+
+        // Accept the token with a listing fee of 5
+        let update_config_msg = ExecuteMsg::UpdateConfig {
+            commission_addr: None,
+            buyer_fee: None,
+            seller_fee: None,
+            listing_fees: None,
+            cw20_listing_fees: Some(vec![Cw20Coin {
+                address: token.to_string(),
+                amount: Uint128::from(5u128),
+            }]),
+            fee_admin_pubkey: None,
+            e_break: None,
+        };
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &update_config_msg,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        // Mint NFT for seller
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        let sale_price = TradePrice::Cw20(Cw20CoinVerified {
+            address: token.clone(),
+            amount: Uint128::from(200u128),
+        });
+
+        // Should error when the listing fee is not paid in the token
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: sale_price.clone(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Create trade successfully, paying the listing fee in the token
+        let create_trade = Cw20ExecuteMsg::Send {
+            contract: trade_contract.to_string(),
+            amount: Uint128::from(5u128),
+            msg: to_binary(&ReceiveMsg::CreateTrade {
+                nft_addr: collection.to_string(),
+                nft_id: TOKEN_ID.to_string(),
+                buyer_addr: buyer.to_string(),
+                sale_price: sale_price.clone(),
+                expires_at: None,
+                escrow_nft: None,
+                on_behalf_of: None,
+            })
+            .unwrap(),
+        };
+        let res = router.execute_contract(seller.clone(), token.clone(), &create_trade, &[]);
+        assert!(res.is_ok());
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        assert_eq!(res.trade.unwrap().price, sale_price);
+
+        //confirm by fee admin
+        let execute_confirm_by_fee_admin = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &execute_confirm_by_fee_admin,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        // Should error when paying with native funds
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(203, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        let receive_execute_trade = to_binary(&ReceiveMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        })
+        .unwrap();
+
+        // Should error with wrong amount. Expected amount 200(price) + 3(fee) = 203
+        let execute_trade = Cw20ExecuteMsg::Send {
+            contract: trade_contract.to_string(),
+            amount: Uint128::from(200u128),
+            msg: receive_execute_trade.clone(),
+        };
+        let res = router.execute_contract(buyer.clone(), token.clone(), &execute_trade, &[]);
+        assert!(res.is_err());
+
+        //Execute trade successfully
+        let execute_trade = Cw20ExecuteMsg::Send {
+            contract: trade_contract.to_string(),
+            amount: Uint128::from(203u128),
+            msg: receive_execute_trade,
+        };
+        let res = router.execute_contract(buyer.clone(), token.clone(), &execute_trade, &[]);
+        assert!(res.is_ok());
+
+        //seller get 197 (203 - 3(seller_fee) - 3(buyer_fee)) and paid 5 listing fee
+        assert_eq!(
+            cw20_balance(&router, &token, &seller),
+            Uint128::from(692u128)
+        );
+        assert_eq!(
+            cw20_balance(&router, &token, &buyer),
+            Uint128::from(297u128)
+        );
+        //commission addr get 3 fee from seller and 3 fee from buyer = 6 token
+        assert_eq!(
+            cw20_balance(&router, &token, &Addr::unchecked("commission_addr")),
+            Uint128::from(6u128)
+        );
+        //fee admin get 5 listing fee
+        assert_eq!(
+            cw20_balance(&router, &token, &Addr::unchecked("fee_admin")),
+            Uint128::from(5u128)
+        );
+        assert_eq!(cw20_balance(&router, &token, &trade_contract), Uint128::zero());
+
+        //check nft has been transfered to buyer
+        let query_owner_msg = Cw721QueryMsg::OwnerOf {
+            token_id: TOKEN_ID.to_string(),
+            include_expired: None,
+        };
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(collection, &query_owner_msg)
+            .unwrap();
+        assert_eq!(res.owner, buyer.to_string());
+    }
+
+    #[test]
+    fn try_create_and_execute_swap() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Seller offers two NFTs, buyer holds the one the seller wants
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+        mint_for(&mut router, &admin, &buyer, &collection, TOKEN_ID + 2);
+
+        let offered_nfts = vec![NftItem {
+            collection: collection.clone(),
+            token_id: (TOKEN_ID + 1).to_string(),
+        }];
+        let wanted_nfts = vec![NftItem {
+            collection: collection.clone(),
+            token_id: (TOKEN_ID + 2).to_string(),
+        }];
+
+        // Should error when the buyer has nothing to hand over
+        let create_swap = ExecuteMsg::CreateSwap {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(0, NATIVE_DENOM).into(),
+            offered_nfts: offered_nfts.clone(),
+            wanted_nfts: vec![],
+            seller_coins: Uint128::from(50u128),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_swap,
+            &coins(60, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        // Should error when the seller does not escrow the coins they add
+        let create_swap = ExecuteMsg::CreateSwap {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(0, NATIVE_DENOM).into(),
+            offered_nfts: offered_nfts.clone(),
+            wanted_nfts: wanted_nfts.clone(),
+            seller_coins: Uint128::from(50u128),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_swap,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Create swap successfully, 10 listing fee + 50 seller coins
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_swap,
+            &coins(60, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        let trade = res.trade.unwrap();
+        assert_eq!(trade.offered_nfts, offered_nfts);
+        assert_eq!(trade.wanted_nfts, wanted_nfts);
+        assert_eq!(trade.seller_coins, Uint128::from(50u128));
+
+        //confirm by fee admin
+        let execute_confirm_by_fee_admin = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &execute_confirm_by_fee_admin,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+
+        // Should error while the buyer has not approved their NFT
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &[],
+        );
+        assert!(res.is_err());
+
+        // Should error when the buyer sends funds nothing is owed for
+        approve(&mut router, &buyer, &collection, &trade_contract, TOKEN_ID + 2);
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Execute swap successfully
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        //check nfts have changed hands
+        for (token_id, owner) in [
+            (TOKEN_ID, &buyer),
+            (TOKEN_ID + 1, &buyer),
+            (TOKEN_ID + 2, &seller),
+        ] {
+            let query_owner_msg = Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            };
+            let res: OwnerOfResponse = router
+                .wrap()
+                .query_wasm_smart(collection.clone(), &query_owner_msg)
+                .unwrap();
+            assert_eq!(res.owner, owner.to_string());
+        }
+
+        //buyer get the 50 coins the seller added
+        let buyer_balances = router.wrap().query_all_balances(buyer.clone()).unwrap();
+        assert_eq!(buyer_balances, coins(2050, NATIVE_DENOM));
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(1940, NATIVE_DENOM));
+
+        //every token that moved has a sale, the extra ones point to the priced one
+        for (token_id, from, to, part_of) in [
+            (TOKEN_ID, &seller, &buyer, None),
+            (TOKEN_ID + 1, &seller, &buyer, Some(1)),
+            (TOKEN_ID + 2, &buyer, &seller, Some(1)),
+        ] {
+            let query_by_token = QueryMsg::GetSalesByToken {
+                nft_collection: collection.to_string(),
+                nft_id: token_id.to_string(),
+                limit: None,
+                start_after: None,
+            };
+            let res: SalesResponse = router
+                .wrap()
+                .query_wasm_smart(trade_contract.clone(), &query_by_token)
+                .unwrap();
+            assert_eq!(res.sales.len(), 1);
+            assert_eq!(&res.sales[0].seller, from);
+            assert_eq!(&res.sales[0].buyer, to);
+            assert_eq!(res.sales[0].part_of, part_of);
+        }
+    }
+
+    #[test]
+    fn try_create_and_execute_bundle() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Mint three NFTs for seller, the last one not approved yet
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 2);
+
+        let nfts: Vec<NftItem> = (TOKEN_ID..TOKEN_ID + 3)
+            .map(|token_id| NftItem {
+                collection: collection.clone(),
+                token_id: token_id.to_string(),
+            })
+            .collect();
+
+        // Should error with an empty bundle
+        let create_bundle = ExecuteMsg::CreateBundle {
+            nfts: vec![],
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_bundle,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        // Should error while one of the NFTs is not approved
+        let create_bundle = ExecuteMsg::CreateBundle {
+            nfts: nfts.clone(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_bundle,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //Create bundle successfully, paying a single listing fee
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 2);
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_bundle,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        assert_eq!(res.trade.unwrap().seller_nfts(), nfts);
+
+        //confirm by fee admin
+        let execute_confirm_by_fee_admin = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &execute_confirm_by_fee_admin,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        //Execute bundle successfully. Expected amount 1000(price) + 15(fee) = 1015
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        //seller get 985 from buyer (1015 - 15(seller_fee) - 15(buyer_fee)) and paid 10 listing fee
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2975, NATIVE_DENOM));
+
+        //check every nft has been transfered to buyer
+        for nft in nfts {
+            let query_owner_msg = Cw721QueryMsg::OwnerOf {
+                token_id: nft.token_id,
+                include_expired: None,
+            };
+            let res: OwnerOfResponse = router
+                .wrap()
+                .query_wasm_smart(collection.clone(), &query_owner_msg)
+                .unwrap();
+            assert_eq!(res.owner, buyer.to_string());
+        }
+    }
+
+    #[test]
+    fn try_create_and_execute_listing() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Mint NFTs for seller
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+
+        // Seller lists both tokens without a designated buyer
+        for (token_id, price) in [(TOKEN_ID, 1000), (TOKEN_ID + 1, 500)] {
+            let create_listing = ExecuteMsg::CreateListing {
+                nft_addr: collection.to_string(),
+                nft_id: token_id.to_string(),
+                sale_price: coin(price, NATIVE_DENOM).into(),
+            };
+            let res = router.execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_listing,
+                &coins(10, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+
+        let query_by_collection = QueryMsg::GetListingsByCollection {
+            collection: collection.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: ListingsResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection)
+            .unwrap();
+        assert_eq!(res.listings.len(), 2usize);
+
+        let query_by_seller = QueryMsg::GetListingsBySeller {
+            seller: seller.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: ListingsResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller)
+            .unwrap();
+        assert_eq!(res.listings.len(), 2usize);
+
+        // Only the seller can cancel a listing
+        let cancel_listing = ExecuteMsg::CancelListing {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        let res =
+            router.execute_contract(buyer.clone(), trade_contract.clone(), &cancel_listing, &[]);
+        assert!(res.is_err());
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &cancel_listing, &[]);
+        assert!(res.is_ok());
+
+        let execute_listing = ExecuteMsg::ExecuteListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+
+        // Should error before the fee admin confirms the listing
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_listing,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        //confirm by fee admin
+        let confirm_listing = ExecuteMsg::ConfirmListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &confirm_listing,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        //Any address can execute the listing. Expected amount 1000(price) + 15(fee) = 1015
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_listing,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        //seller get 985 from buyer and paid 2 listing fees, the cancelled one was refunded
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2975, NATIVE_DENOM));
+        // the fee admin earns the listing fee once the listing sells
+        let fee_admin_balances =
+            router.wrap().query_all_balances(Addr::unchecked("fee_admin")).unwrap();
+        assert_eq!(fee_admin_balances, coins(10, NATIVE_DENOM));
+
+        let query_owner_msg = Cw721QueryMsg::OwnerOf {
+            token_id: TOKEN_ID.to_string(),
+            include_expired: None,
+        };
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(collection.clone(), &query_owner_msg)
+            .unwrap();
+        assert_eq!(res.owner, buyer.to_string());
+
+        //listing has been removed
+        let query_listing = QueryMsg::GetListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: ListingResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_listing)
+            .unwrap();
+        assert_eq!(res.listing, None);
+    }
+
+    #[test]
+    fn try_hold_listing_fee_until_listing_ends() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        let create_listing = ExecuteMsg::CreateListing {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+        };
+        let query_listing = QueryMsg::GetListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let balance_of = |router: &App, addr: &Addr| {
+            router.wrap().query_balance(addr, NATIVE_DENOM).unwrap().amount.u128()
+        };
+        let fee_admin = Addr::unchecked("fee_admin");
+
+        // The contract holds the listing fee
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_listing,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(balance_of(&router, &trade_contract), 10);
+        assert_eq!(balance_of(&router, &fee_admin), 0);
+
+        // A listing rejected by the fee admin gets its fee back
+        let reject_listing = ExecuteMsg::ConfirmListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: false,
+        };
+        let res =
+            router.execute_contract(fee_admin.clone(), trade_contract.clone(), &reject_listing, &[]);
+        assert!(res.is_ok());
+        assert_eq!(balance_of(&router, &seller), INITIAL_BALANCE);
+        assert_eq!(balance_of(&router, &trade_contract), 0);
+
+        // Selling the token through a trade drops the listing left behind and keeps its fee
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_listing,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let execute_trade =
+            confirmed_trade(&mut router, &trade_contract, &collection, &seller, &buyer, TOKEN_ID);
+        let res = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade,
+                &coins(1015, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert!(res.events.iter().any(|event| event.ty == "wasm-drop-stale-listing"));
+
+        let res: ListingResponse =
+            router.wrap().query_wasm_smart(trade_contract.clone(), &query_listing).unwrap();
+        assert_eq!(res.listing, None);
+        assert_eq!(balance_of(&router, &fee_admin), 20);
+        assert_eq!(balance_of(&router, &trade_contract), 0);
+    }
+
+    #[test]
+    fn try_create_withdraw_and_accept_offer() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Mint NFT for seller
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+
+        // Should error when bidding on the sender's own token
+        let create_offer = ExecuteMsg::CreateOffer {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            price: coin(1000, NATIVE_DENOM).into(),
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_offer,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        // Should error when the escrow does not cover price and buyer fee
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &create_offer,
+            &coins(1000, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        // Two bidders escrow their offers. Expected amount 1000(price) + 15(fee) = 1015
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &create_offer,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let lower_offer = ExecuteMsg::CreateOffer {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            price: coin(500, NATIVE_DENOM).into(),
+        };
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &lower_offer,
+            &coins(507, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let query_by_token = QueryMsg::GetOffersByToken {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            limit: None,
+            start_after: None,
+        };
+        let res: OffersResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_token)
+            .unwrap();
+        assert_eq!(res.offers.len(), 2usize);
+
+        let query_by_collection = QueryMsg::GetOffersByCollection {
+            collection: collection.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: OffersResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection)
+            .unwrap();
+        assert_eq!(res.offers.len(), 2usize);
+
+        let query_by_bidder = QueryMsg::GetOffersByBidder {
+            bidder: buyer.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: OffersResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_bidder)
+            .unwrap();
+        assert_eq!(res.offers.len(), 1usize);
+        assert_eq!(res.offers[0].price, coin(1000, NATIVE_DENOM).into());
+
+        // The lower bidder withdraws and gets the escrow back
+        let withdraw_offer = ExecuteMsg::WithdrawOffer {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res =
+            router.execute_contract(admin.clone(), trade_contract.clone(), &withdraw_offer, &[]);
+        assert!(res.is_ok());
+        let admin_balances = router.wrap().query_all_balances(admin.clone()).unwrap();
+        assert_eq!(admin_balances, coins(2000, NATIVE_DENOM));
+
+        let query_offer = QueryMsg::GetOffer {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            bidder: admin.to_string(),
+        };
+        let res: OfferResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_offer)
+            .unwrap();
+        assert_eq!(res.offer, None);
+
+        let accept_offer = ExecuteMsg::AcceptOffer {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            bidder: buyer.to_string(),
+        };
+
+        // Only the owner can accept
+        let res =
+            router.execute_contract(buyer.clone(), trade_contract.clone(), &accept_offer, &[]);
+        assert!(res.is_err());
+
+        // Should error while the contract is not approved
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &accept_offer, &[]);
+        assert!(res.is_err());
+
+        //Accept offer successfully
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &accept_offer, &[]);
+        assert!(res.is_ok());
+
+        //seller get 985 (1000 - 15(seller_fee))
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2985, NATIVE_DENOM));
+
+        //commission addr get 15 fee from seller and 15 fee from buyer = 30 coin
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(30, NATIVE_DENOM));
+
+        let query_owner_msg = Cw721QueryMsg::OwnerOf {
+            token_id: TOKEN_ID.to_string(),
+            include_expired: None,
+        };
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(collection.clone(), &query_owner_msg)
+            .unwrap();
+        assert_eq!(res.owner, buyer.to_string());
+    }
+
+    #[test]
+    fn try_create_and_fill_collection_offer() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Two holders of the collection
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        mint_for(&mut router, &admin, &admin, &collection, TOKEN_ID + 1);
+
+        // Should error on a zero quantity
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::CreateCollectionOffer {
+                nft_collection: collection.to_string(),
+                price: coin(500, NATIVE_DENOM).into(),
+                quantity: 0,
+            },
+            &[],
+        );
+        assert!(res.is_err());
+
+        // Bid for any two tokens. Expected amount 2 * (500(price) + 7(fee)) = 1014
+        let create_offer = ExecuteMsg::CreateCollectionOffer {
+            nft_collection: collection.to_string(),
+            price: coin(500, NATIVE_DENOM).into(),
+            quantity: 2,
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &create_offer,
+            &coins(507, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &create_offer,
+            &coins(1014, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let query_by_collection = QueryMsg::GetCollectionOffersByCollection {
+            collection: collection.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: CollectionOffersResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection)
+            .unwrap();
+        assert_eq!(res.offers.len(), 1usize);
+        assert_eq!(res.offers[0].quantity, 2);
+
+        let query_by_bidder = QueryMsg::GetCollectionOffersByBidder {
+            bidder: buyer.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: CollectionOffersResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_bidder)
+            .unwrap();
+        assert_eq!(res.offers.len(), 1usize);
+
+        // Should error when the sender does not hold the token
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptCollectionOffer {
+                nft_collection: collection.to_string(),
+                nft_id: TOKEN_ID.to_string(),
+                bidder: buyer.to_string(),
+            },
+            &[],
+        );
+        assert!(res.is_err());
+
+        // First holder fills one of the two tokens
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptCollectionOffer {
+                nft_collection: collection.to_string(),
+                nft_id: TOKEN_ID.to_string(),
+                bidder: buyer.to_string(),
+            },
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let query_offer = QueryMsg::GetCollectionOffer {
+            nft_collection: collection.to_string(),
+            bidder: buyer.to_string(),
+        };
+        let res: CollectionOfferResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_offer)
+            .unwrap();
+        assert_eq!(res.offer.unwrap().quantity, 1);
+
+        // Second holder fills the last one
+        approve(&mut router, &admin, &collection, &trade_contract, TOKEN_ID + 1);
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::AcceptCollectionOffer {
+                nft_collection: collection.to_string(),
+                nft_id: (TOKEN_ID + 1).to_string(),
+                bidder: buyer.to_string(),
+            },
+            &[],
+        );
+        assert!(res.is_ok());
+
+        // The filled offer is gone
+        let res: CollectionOfferResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_offer)
+            .unwrap();
+        assert_eq!(res.offer, None);
+
+        //each holder get 493 (500 - 7(seller_fee))
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2493, NATIVE_DENOM));
+        let admin_balances = router.wrap().query_all_balances(admin.clone()).unwrap();
+        assert_eq!(admin_balances, coins(2493, NATIVE_DENOM));
+
+        //commission addr get 7 + 7 fee on each of the two tokens = 28 coin
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(28, NATIVE_DENOM));
+
+        for token_id in [TOKEN_ID, TOKEN_ID + 1] {
+            let res: OwnerOfResponse = router
+                .wrap()
+                .query_wasm_smart(
+                    collection.clone(),
+                    &Cw721QueryMsg::OwnerOf {
+                        token_id: token_id.to_string(),
+                        include_expired: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.owner, buyer.to_string());
+        }
+    }
+
+    #[test]
+    fn try_expire_and_prune_trades() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+
+        let height = router.block_info().height;
+
+        // Should error when the trade is already expired
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: Some(Expiration::AtHeight(height)),
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let err = router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade,
+                &coins(10, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::TradeExpired {}));
+
+        // One trade expires in 5 blocks, the other never does
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: Some(Expiration::AtHeight(height + 5)),
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        // Nothing to prune yet
+        let prune = ExecuteMsg::PruneExpiredTrades {
+            limit: None,
+            start_after: None,
+        };
+        let res = router.execute_contract(buyer.clone(), trade_contract.clone(), &prune, &[]);
+        assert!(res.is_ok());
+        let query_by_buyer = QueryMsg::GetTradesByBuyer {
+            buyer: buyer.clone(),
+            limit: None,
+            include_expired: None,
+            start_after: None,
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_buyer)
+            .unwrap();
+        assert_eq!(res.trades.len(), 2usize);
+
+        router.update_block(|block| block.height += 5);
+
+        // Expired trades are hidden unless asked for
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_buyer)
+            .unwrap();
+        assert_eq!(res.trades.len(), 1usize);
+        assert_eq!(res.trades[0].nft_id, (TOKEN_ID + 1).to_string());
+        let query_by_seller = QueryMsg::GetTradesBySeller {
+            seller: seller.clone(),
+            limit: None,
+            include_expired: Some(true),
+            start_after: None,
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller)
+            .unwrap();
+        assert_eq!(res.trades.len(), 2usize);
+
+        // Should error confirming an expired trade
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            is_confirmed_by_fee_admin: true,
+        };
+        let err = router
+            .execute_contract(
+                Addr::unchecked("fee_admin".to_string()),
+                trade_contract.clone(),
+                &confirm_trade,
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::TradeExpired {}));
+
+        // Anyone can prune, one trade per call here, only the expired trade goes
+        let prune_page = |start_after: Option<TradeOffset>| ExecuteMsg::PruneExpiredTrades {
+            limit: Some(1),
+            start_after,
+        };
+        let attribute = |res: &cw_multi_test::AppResponse, key: &str| {
+            res.events
+                .iter()
+                .flat_map(|event| event.attributes.iter())
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.clone())
+        };
+        let next_offset = |res: &cw_multi_test::AppResponse| {
+            attribute(res, "next_nft_id").map(|nft_id| {
+                TradeOffset::new(
+                    attribute(res, "next_buyer").unwrap(),
+                    attribute(res, "next_nft_collection").unwrap(),
+                    nft_id,
+                )
+            })
+        };
+        let res = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &prune_page(None), &[])
+            .unwrap();
+        assert_eq!(attribute(&res, "pruned"), Some("1".to_string()));
+        let next = next_offset(&res).unwrap();
+        assert_eq!(next.nft_id, TOKEN_ID.to_string());
+
+        let res = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &prune_page(Some(next)), &[])
+            .unwrap();
+        assert_eq!(attribute(&res, "pruned"), Some("0".to_string()));
+        let next = next_offset(&res).unwrap();
+        assert_eq!(next.nft_id, (TOKEN_ID + 1).to_string());
+
+        // the last page is not full, nothing is left to check
+        let res = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &prune_page(Some(next)), &[])
+            .unwrap();
+        assert_eq!(next_offset(&res), None);
+
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller)
+            .unwrap();
+        assert_eq!(res.trades.len(), 1usize);
+        assert_eq!(res.trades[0].nft_id, (TOKEN_ID + 1).to_string());
+    }
+
+    #[test]
+    fn try_create_and_execute_trade_in_escrow() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+
+        // The contract takes the NFT as soon as the trade is created
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: Some(true),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), trade_contract.to_string());
+
+        // Only the seller can cancel as the seller
+        let cancel_trade = ExecuteMsg::CancelTrade {
+            buyer: None,
+            seller: Some(seller.to_string()),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res = router.execute_contract(buyer.clone(), trade_contract.clone(), &cancel_trade, &[]);
+        assert!(res.is_err());
+
+        // Cancelling gives the NFT back
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &cancel_trade, &[]);
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), seller.to_string());
+
+        // So does a rejection by the fee admin
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: Some(true),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let reject_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: false,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &reject_trade,
+            &[],
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID + 1), seller.to_string());
+
+        // Escrow again, confirm and execute
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: Some(true),
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &confirm_trade,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), buyer.to_string());
+
+        //seller get 985 from buyer and paid 2 listing fees of 10, the rejected trade's was refunded
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2965, NATIVE_DENOM));
+    }
+
+    fn sign_fee_confirmation(
+        router: &App,
+        trade_contract: &Addr,
+        signing_key: &SigningKey,
+        buyer: &Addr,
+        collection: &Addr,
+        token_id: u32,
+        confirmation: FeeConfirmation,
+    ) -> SignedFeeConfirmation {
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: token_id.to_string(),
+        };
+        let res: TradeResponse = router.wrap().query_wasm_smart(trade_contract, &trade_msg).unwrap();
+        let payload =
+            fee_confirmation_payload(trade_contract, &res.trade.unwrap(), &confirmation).unwrap();
+        let signature: Signature = signing_key.sign(&payload);
+        SignedFeeConfirmation {
+            confirmation,
+            signature: Binary::from(signature.as_ref()),
+        }
+    }
+
+    #[test]
+    fn try_execute_trade_with_signed_fees() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // Register the fee admin's public key
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let pubkey = signing_key.verifying_key().to_encoded_point(true);
+        let update_config_msg = ExecuteMsg::UpdateConfig {
+            commission_addr: None,
+            buyer_fee: None,
+            seller_fee: None,
+            listing_fees: None,
+            cw20_listing_fees: None,
+            fee_admin_pubkey: Some(Binary::from(pubkey.as_bytes())),
+            e_break: None,
+        };
+        let res =
+            router.execute_contract(admin.clone(), trade_contract.clone(), &update_config_msg, &[]);
+        assert!(res.is_ok());
+
+        for (token_id, price) in [(TOKEN_ID, 1000), (TOKEN_ID + 1, 500)] {
+            mint_for(&mut router, &admin, &seller, &collection, token_id);
+            approve(&mut router, &seller, &collection, &trade_contract, token_id);
+            let create_trade = ExecuteMsg::CreateTrade {
+                nft_addr: collection.to_string(),
+                nft_id: token_id.to_string(),
+                buyer_addr: buyer.to_string(),
+                sale_price: coin(price, NATIVE_DENOM).into(),
+                expires_at: None,
+                escrow_nft: None,
+                on_behalf_of: None,
+            };
+            let res = router.execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade,
+                &coins(10, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+
+        let confirmation = FeeConfirmation {
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            expires_at: Expiration::Never {},
+            nonce: 1,
+        };
+        let signed = sign_fee_confirmation(
+            &router,
+            &trade_contract,
+            &signing_key,
+            &buyer,
+            &collection,
+            TOKEN_ID,
+            confirmation.clone(),
+        );
+
+        // Should error when the signed fees are altered
+        let mut tampered = signed.clone();
+        tampered.confirmation.buyer_fee_pct = Decimal::zero();
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: Some(tampered),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(1000, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidFeeSignature {}));
+
+        // Should error when the fees were signed for another deployment
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetTrade {
+                    buyer: buyer.to_string(),
+                    nft_collection: collection.to_string(),
+                    nft_id: TOKEN_ID.to_string(),
+                },
+            )
+            .unwrap();
+        let payload = fee_confirmation_payload(
+            &Addr::unchecked("other_deployment"),
+            &res.trade.unwrap(),
+            &confirmation,
+        )
+        .unwrap();
+        let signature: Signature = signing_key.sign(&payload);
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: Some(SignedFeeConfirmation {
+                confirmation,
+                signature: Binary::from(signature.as_ref()),
+            }),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(1010, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidFeeSignature {}));
+
+        // Should error while confirmations are paused, signed ones included
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: Some(signed),
+        };
+        let set_pause = |paused| ExecuteMsg::SetPause {
+            action: PauseAction::Confirm,
+            paused,
+            collection: None,
+        };
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(true), &[])
+            .unwrap();
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(1010, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::ActionPaused { .. }));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(false), &[])
+            .unwrap();
+
+        // Execute with the signed fees. Expected amount 1000(price) + 10(fee) = 1010
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(1010, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), buyer.to_string());
+
+        //seller get 990 (1000 - 10(seller_fee)) and paid 2 listing fees of 10
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2970, NATIVE_DENOM));
+
+        // Should error when the nonce is reused, even for another trade. Expected amount 500 + 5 = 505
+        let confirmation = FeeConfirmation {
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            expires_at: Expiration::Never {},
+            nonce: 1,
+        };
+        let signed = sign_fee_confirmation(
+            &router,
+            &trade_contract,
+            &signing_key,
+            &buyer,
+            &collection,
+            TOKEN_ID + 1,
+            confirmation,
+        );
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            fee_confirmation: Some(signed),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(505, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::NonceAlreadyUsed {}));
+
+        // Should error when the confirmation expired
+        let confirmation = FeeConfirmation {
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            expires_at: Expiration::AtHeight(router.block_info().height),
+            nonce: 2,
+        };
+        let signed = sign_fee_confirmation(
+            &router,
+            &trade_contract,
+            &signing_key,
+            &buyer,
+            &collection,
+            TOKEN_ID + 1,
+            confirmation,
+        );
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            fee_confirmation: Some(signed),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(505, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::FeeConfirmationExpired {}));
+
+        // Should error once the seller recreates the trade as a bundle at the same price
+        let confirmation = FeeConfirmation {
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            expires_at: Expiration::Never {},
+            nonce: 4,
+        };
+        let signed = sign_fee_confirmation(
+            &router,
+            &trade_contract,
+            &signing_key,
+            &buyer,
+            &collection,
+            TOKEN_ID + 1,
+            confirmation,
+        );
+        let cancel_trade = ExecuteMsg::CancelTrade {
+            buyer: None,
+            seller: Some(seller.to_string()),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        router
+            .execute_contract(seller.clone(), trade_contract.clone(), &cancel_trade, &[])
+            .unwrap();
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 2);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 2);
+        let create_bundle = ExecuteMsg::CreateBundle {
+            nfts: vec![
+                NftItem {
+                    collection: collection.clone(),
+                    token_id: (TOKEN_ID + 1).to_string(),
+                },
+                NftItem {
+                    collection: collection.clone(),
+                    token_id: (TOKEN_ID + 2).to_string(),
+                },
+            ],
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(500, NATIVE_DENOM).into(),
+            on_behalf_of: None,
+        };
+        router
+            .execute_contract(seller.clone(), trade_contract.clone(), &create_bundle, &coins(10, NATIVE_DENOM))
+            .unwrap();
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            fee_confirmation: Some(signed),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(505, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidFeeSignature {}));
+
+        // Should error on the old fee admin's signature once the role changed hands
+        let propose_msg = ExecuteMsg::ProposeFeeAdmin {
+            new_fee_admin: "new_fee_admin".to_string(),
+            expires_at: None,
+        };
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &propose_msg, &[])
+            .unwrap();
+        router
+            .execute_contract(
+                Addr::unchecked("new_fee_admin"),
+                trade_contract.clone(),
+                &ExecuteMsg::AcceptFeeAdmin {},
+                &[],
+            )
+            .unwrap();
+        let config: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.fee_admin_pubkey, None);
+        let confirmation = FeeConfirmation {
+            seller_fee_pct: Decimal::percent(1),
+            buyer_fee_pct: Decimal::percent(1),
+            expires_at: Expiration::Never {},
+            nonce: 3,
+        };
+        let signed = sign_fee_confirmation(
+            &router,
+            &trade_contract,
+            &signing_key,
+            &buyer,
+            &collection,
+            TOKEN_ID + 1,
+            confirmation,
+        );
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            fee_confirmation: Some(signed),
+        };
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade_msg,
+                &coins(505, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::MissingFeeAdminPubkey {}));
+    }
+
+    #[test]
+    fn try_trade_through_operators() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+        let desk = Addr::unchecked("desk");
+        router
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: desk.to_string(),
+                amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+            }))
+            .unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+
+        // The seller lets the desk trade this collection for 100 blocks
+        let register_operator = ExecuteMsg::RegisterOperator {
+            operator: desk.to_string(),
+            expires_at: Some(Expiration::AtHeight(router.block_info().height + 100)),
+            collections: Some(vec![collection.to_string()]),
+        };
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &register_operator, &[]);
+        assert!(res.is_ok());
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &register_operator, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::OperatorAlreadyRegistered {}));
+
+        let query_operators = QueryMsg::GetOperators {
+            principal: seller.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: OperatorsResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_operators)
+            .unwrap();
+        assert_eq!(res.operators.len(), 1usize);
+        assert_eq!(res.operators[0].operator, desk);
+
+        // The desk creates the trade, the seller stays the seller
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: Some(seller.to_string()),
+        };
+        let res = router.execute_contract(
+            desk.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        assert_eq!(res.trade.unwrap().seller, seller);
+
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &confirm_trade,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        // Should error while the desk is not the buyer's operator
+        let execute_trade_msg = ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            fee_confirmation: None,
+        };
+        let res = router.execute_contract(
+            desk.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_err());
+
+        // The buyer registers the desk too, which then executes for them
+        let register_operator = ExecuteMsg::RegisterOperator {
+            operator: desk.to_string(),
+            expires_at: None,
+            collections: None,
+        };
+        let res =
+            router.execute_contract(buyer.clone(), trade_contract.clone(), &register_operator, &[]);
+        assert!(res.is_ok());
+        let res = router.execute_contract(
+            desk.clone(),
+            trade_contract.clone(),
+            &execute_trade_msg,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        // NFT and proceeds go to the principals
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), buyer.to_string());
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2985, NATIVE_DENOM));
+
+        // Should error when the swap wants an NFT outside the desk's collections
+        let cw721_id = router.store_code(contract_nft());
+        let msg = Cw721InstantiateMsg {
+            name: String::from("Other punk"),
+            symbol: String::from("OP"),
+            minter: admin.to_string(),
+        };
+        let other_collection = router
+            .instantiate_contract(cw721_id, admin.clone(), &msg, &[], "other", None)
+            .unwrap();
+        mint_for(&mut router, &admin, &buyer, &other_collection, TOKEN_ID);
+        let create_swap = ExecuteMsg::CreateSwap {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(100, NATIVE_DENOM).into(),
+            offered_nfts: vec![],
+            wanted_nfts: vec![NftItem {
+                collection: other_collection.clone(),
+                token_id: TOKEN_ID.to_string(),
+            }],
+            seller_coins: Uint128::zero(),
+            on_behalf_of: Some(seller.to_string()),
+        };
+        let err = router
+            .execute_contract(
+                desk.clone(),
+                trade_contract.clone(),
+                &create_swap,
+                &coins(10, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::UnauthorizedOperator {}));
+
+        // Should error once the registration expired
+        router.update_block(|block| block.height += 100);
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: Some(seller.to_string()),
+        };
+        let err = router
+            .execute_contract(
+                desk.clone(),
+                trade_contract.clone(),
+                &create_trade,
+                &coins(10, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::UnauthorizedOperator {}));
+
+        // Revoke
+        let revoke_operator = ExecuteMsg::RevokeOperator {
+            operator: desk.to_string(),
+        };
+        let res =
+            router.execute_contract(seller.clone(), trade_contract.clone(), &revoke_operator, &[]);
+        assert!(res.is_ok());
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &revoke_operator, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::OperatorNotRegistered {}));
+    }
+
+    #[test]
+    fn try_reserve_token_for_one_trade() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        let create_trade = |buyer: &Addr| ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade(&buyer),
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        // Should error on a second trade for the same token, whoever the buyer
+        for other_buyer in [&buyer, &admin] {
+            let err = router
+                .execute_contract(
+                    seller.clone(),
+                    trade_contract.clone(),
+                    &create_trade(other_buyer),
+                    &coins(10, NATIVE_DENOM),
+                )
+                .unwrap_err();
+            assert!(matches!(err.downcast().unwrap(), ContractError::TokenReserved {}));
+        }
+
+        let query_reservation = QueryMsg::GetTokenReservation {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_reservation)
+            .unwrap();
+        assert_eq!(res.trade.unwrap().buyer, buyer);
+
+        // Cancelling frees the token
+        let cancel_trade = ExecuteMsg::CancelTrade {
+            buyer: Some(buyer.to_string()),
+            seller: None,
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res = router.execute_contract(buyer.clone(), trade_contract.clone(), &cancel_trade, &[]);
+        assert!(res.is_ok());
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_reservation)
+            .unwrap();
+        assert_eq!(res.trade, None);
+
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade(&admin),
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        // Selling the token another way drops the trade holding it
+        let create_listing = ExecuteMsg::CreateListing {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_listing,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let confirm_listing = ExecuteMsg::ConfirmListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin".to_string()),
+            trade_contract.clone(),
+            &confirm_listing,
+            &[],
+        );
+        assert!(res.is_ok());
+        let execute_listing = ExecuteMsg::ExecuteListing {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_listing,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), buyer.to_string());
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: admin.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        assert_eq!(res.trade, None);
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_reservation)
+            .unwrap();
+        assert_eq!(res.trade, None);
+    }
+
+    // Create a 1000 uluna trade of `token_id` from seller to buyer and confirm it, returning the execute message
+    fn confirmed_trade(
+        router: &mut App,
+        trade_contract: &Addr,
+        collection: &Addr,
+        seller: &Addr,
+        buyer: &Addr,
+        token_id: u32,
+    ) -> ExecuteMsg {
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: token_id.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: token_id.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin"),
+            trade_contract.clone(),
+            &confirm_trade,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        ExecuteMsg::ExecuteTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: token_id.to_string(),
+            fee_confirmation: None,
+        }
+    }
+
+    #[test]
+    fn try_execute_trade_with_royalties() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        // buyer pays for three trades
+        router
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: buyer.to_string(),
+                amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+            }))
+            .unwrap();
+
+        let royalty_nft_id = router.store_code(contract_royalty_nft());
+        let msg = Cw721InstantiateMsg {
+            name: String::from("Royal punk"),
+            symbol: String::from("RP"),
+            minter: admin.to_string(),
+        };
+        let royalty_collection = router
+            .instantiate_contract(royalty_nft_id, admin.clone(), &msg, &[], "royalty", None)
+            .unwrap();
+
+        for token_id in [TOKEN_ID, TOKEN_ID + 1] {
+            mint_for(&mut router, &admin, &seller, &royalty_collection, token_id);
+            approve(&mut router, &seller, &royalty_collection, &trade_contract, token_id);
+        }
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        // Royalties are honored by default
+        let query_policy = QueryMsg::GetRoyaltyPolicy {
+            collection: royalty_collection.to_string(),
+        };
+        let res: RoyaltyPolicyResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_policy)
+            .unwrap();
+        assert_eq!(res.policy, RoyaltyPolicy::Honor);
+
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &royalty_collection,
+            &seller,
+            &buyer,
+            TOKEN_ID,
+        );
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &royalty_collection, TOKEN_ID), buyer.to_string());
+
+        // seller gets 1000 - 15(seller_fee) - 50(royalty), creator gets 5%
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2925, NATIVE_DENOM));
+        let creator_balances = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("creator"))
+            .unwrap();
+        assert_eq!(creator_balances, coins(50, NATIVE_DENOM));
+
+        // Should error setting the policy when not admin
+        let set_policy = |collection: &Addr, policy: RoyaltyPolicy| ExecuteMsg::SetRoyaltyPolicy {
+            collection: collection.to_string(),
+            policy,
+        };
+        let err = router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &set_policy(&royalty_collection, RoyaltyPolicy::Ignore),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // Ignored royalties are not paid
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &set_policy(&royalty_collection, RoyaltyPolicy::Ignore),
+            &[],
+        );
+        assert!(res.is_ok());
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &royalty_collection,
+            &seller,
+            &buyer,
+            TOKEN_ID + 1,
+        );
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(3900, NATIVE_DENOM));
+        let creator_balances = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("creator"))
+            .unwrap();
+        assert_eq!(creator_balances, coins(50, NATIVE_DENOM));
+
+        // Should error executing when enforced on a collection without royalties
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &set_policy(&collection, RoyaltyPolicy::Enforce),
+            &[],
+        );
+        assert!(res.is_ok());
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &collection,
+            &seller,
+            &buyer,
+            TOKEN_ID,
+        );
+        let err = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade,
+            &coins(1015, NATIVE_DENOM),
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::RoyaltyUnavailable {}));
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), seller.to_string());
+    }
+
+    #[test]
+    fn try_set_collection_fees() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        let global_fees = FeeSchedule {
+            buyer_fee: Decimal::from_ratio(15u128, 1000u128),
+            seller_fee: Decimal::from_ratio(15u128, 1000u128),
+            partner: false,
+        };
+        let query_fees = QueryMsg::GetCollectionFees {
+            collection: collection.to_string(),
+        };
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert_eq!(res.fees, global_fees);
+        assert!(!res.is_override);
+
+        let set_fees = |buyer_fee: Decimal, seller_fee: Decimal, partner: bool| {
+            ExecuteMsg::SetCollectionFees {
+                collection: collection.to_string(),
+                buyer_fee,
+                seller_fee,
+                partner,
+            }
+        };
+
+        // Should error when not admin
+        let err = router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &set_fees(Decimal::zero(), Decimal::zero(), true),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // Should error when partnership fees exceed the global fees
+        let err = router
+            .execute_contract(
+                admin.clone(),
+                trade_contract.clone(),
+                &set_fees(Decimal::percent(2), Decimal::zero(), true),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidPartnerFees {}));
+
+        // Partnership collection trades fee free on the seller side
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &set_fees(Decimal::percent(1), Decimal::zero(), true),
+            &[],
+        );
+        assert!(res.is_ok());
+        let partner_fees = FeeSchedule {
+            buyer_fee: Decimal::percent(1),
+            seller_fee: Decimal::zero(),
+            partner: true,
+        };
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert_eq!(res.fees, partner_fees);
+        assert!(res.is_override);
+
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        // The trade keeps the fees it was created with. Returning to the higher global fees
+        // waits out the timelock
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::RemoveCollectionFees {
+                collection: collection.to_string(),
+            },
+            &[],
+        );
+        assert!(res.is_ok());
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert_eq!(res.fees, partner_fees);
+        let res: PendingCollectionFeeChangeResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetPendingCollectionFeeChange {
+                    collection: collection.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.change.unwrap().fees, None);
+
+        let apply_change = ExecuteMsg::ApplyCollectionFeeChange {
+            collection: collection.to_string(),
+        };
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &apply_change, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::FeeChangeLocked { .. }));
+        router.update_block(|block| block.time = block.time.plus_seconds(86400));
+        let res = router.execute_contract(buyer.clone(), trade_contract.clone(), &apply_change, &[]);
+        assert!(res.is_ok());
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert_eq!(res.fees, global_fees);
+        assert!(!res.is_override);
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        let trade = res.trade.unwrap();
+        assert_eq!(trade.buyer_fee, Decimal::percent(1));
+        assert_eq!(trade.seller_fee, Decimal::zero());
+    }
+
+    #[test]
+    fn try_execute_trade_with_holder_discount() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        let cw721_id = router.store_code(contract_nft());
+        let msg = Cw721InstantiateMsg {
+            name: String::from("Members club"),
+            symbol: String::from("MC"),
+            minter: admin.to_string(),
+        };
+        let membership = router
+            .instantiate_contract(cw721_id, admin.clone(), &msg, &[], "membership", None)
+            .unwrap();
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &buyer, &membership, 1);
+        mint_for(&mut router, &admin, &seller, &membership, 2);
+
+        let set_discount = |buyer_discount: Decimal, seller_discount: Decimal| {
+            ExecuteMsg::SetHolderDiscount {
+                membership_collection: membership.to_string(),
+                buyer_discount,
+                seller_discount,
+            }
+        };
+
+        // Should error when not admin
+        let err = router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &set_discount(Decimal::one(), Decimal::percent(50)),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // Should error on a discount above 100%
+        let err = router
+            .execute_contract(
+                admin.clone(),
+                trade_contract.clone(),
+                &set_discount(Decimal::percent(150), Decimal::zero()),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidDiscount {}));
+
+        // Members trade without buyer fee and at half the seller fee
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &set_discount(Decimal::one(), Decimal::percent(50)),
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let discount = FeeDiscount {
+            buyer_discount: Decimal::one(),
+            seller_discount: Decimal::percent(50),
+        };
+        let query_discount = |address: &Addr| QueryMsg::GetHolderDiscount {
+            address: address.to_string(),
+        };
+        let res: HolderDiscountResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_discount(&buyer))
+            .unwrap();
+        assert_eq!(res.discount, discount);
+        let res: HolderDiscountResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_discount(&admin))
+            .unwrap();
+        assert_eq!(res.discount, FeeDiscount::default());
+
+        // a membership collection that can not be queried is skipped
+        let res = router.execute_contract(
+            admin.clone(),
+            trade_contract.clone(),
+            &ExecuteMsg::SetHolderDiscount {
+                membership_collection: "not_a_collection".to_string(),
+                buyer_discount: Decimal::one(),
+                seller_discount: Decimal::one(),
+            },
+            &[],
+        );
+        assert!(res.is_ok());
+        let res: HolderDiscountResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_discount(&buyer))
+            .unwrap();
+        assert_eq!(res.discount, discount);
+
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &collection,
+            &seller,
+            &buyer,
+            TOKEN_ID,
+        );
+
+        // Should error paying the undiscounted buyer fee
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade,
+                &coins(1015, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::PaymentAmountMismatch {}));
+
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade,
+            &coins(1000, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), buyer.to_string());
+
+        // seller gets 1000 - 7(half seller_fee)
+        let seller_balances = router.wrap().query_all_balances(seller.clone()).unwrap();
+        assert_eq!(seller_balances, coins(2983, NATIVE_DENOM));
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(7, NATIVE_DENOM));
+
+        // Listings settle with the discounts too
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+        let create_listing = ExecuteMsg::CreateListing {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+        };
+        router
+            .execute_contract(seller.clone(), trade_contract.clone(), &create_listing, &coins(10, NATIVE_DENOM))
+            .unwrap();
+        let confirm_listing = ExecuteMsg::ConfirmListing {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        router
+            .execute_contract(Addr::unchecked("fee_admin"), trade_contract.clone(), &confirm_listing, &[])
+            .unwrap();
+        let execute_listing = ExecuteMsg::ExecuteListing {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_listing,
+            &coins(1000, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID + 1), buyer.to_string());
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(14, NATIVE_DENOM));
+
+        // Accepted offers settle with the discounts too, the bidder gets the waived fee back
+        router
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: buyer.to_string(),
+                amount: coins(1015, NATIVE_DENOM),
+            }))
+            .unwrap();
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 2);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 2);
+        let create_offer = ExecuteMsg::CreateOffer {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 2).to_string(),
+            price: coin(1000, NATIVE_DENOM).into(),
+        };
+        router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &create_offer, &coins(1015, NATIVE_DENOM))
+            .unwrap();
+        let accept_offer = ExecuteMsg::AcceptOffer {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 2).to_string(),
+            bidder: buyer.to_string(),
+        };
+        router
+            .execute_contract(seller.clone(), trade_contract.clone(), &accept_offer, &[])
+            .unwrap();
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID + 2), buyer.to_string());
+        let buyer_balances = router.wrap().query_all_balances(buyer.clone()).unwrap();
+        assert_eq!(buyer_balances, coins(15, NATIVE_DENOM));
+        let commission_addr_bal = router
+            .wrap()
+            .query_all_balances(Addr::unchecked("commission_addr"))
+            .unwrap();
+        assert_eq!(commission_addr_bal, coins(21, NATIVE_DENOM));
+
+        // Should error past the cap on membership collections, updates stay open
+        let set_discount_for = |membership_collection: &str| ExecuteMsg::SetHolderDiscount {
+            membership_collection: membership_collection.to_string(),
+            buyer_discount: Decimal::percent(10),
+            seller_discount: Decimal::percent(10),
+        };
+        for membership_collection in ["club_3", "club_4", "club_5"] {
+            router
+                .execute_contract(admin.clone(), trade_contract.clone(), &set_discount_for(membership_collection), &[])
+                .unwrap();
+        }
+        let err = router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_discount_for("club_6"), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::TooManyHolderDiscounts { max: 5 }));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_discount_for("club_5"), &[])
+            .unwrap();
+    }
+
+    fn listing_fee_outcome(res: &cw_multi_test::AppResponse) -> Option<String> {
+        res.events
+            .iter()
+            .find(|event| event.ty == "wasm-listing-fee")
+            .and_then(|event| event.attributes.iter().find(|attr| attr.key == "outcome"))
+            .map(|attr| attr.value.clone())
+    }
+
+    #[test]
+    fn try_hold_listing_fee_until_trade_ends() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let balance = |router: &App, addr: &str| {
+            router
+                .wrap()
+                .query_balance(addr, NATIVE_DENOM)
+                .unwrap()
+                .amount
+                .u128()
+        };
+
+        // The contract holds the fee while the trade is live
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        assert_eq!(balance(&router, trade_contract.as_str()), 10);
+        assert_eq!(balance(&router, "fee_admin"), 0);
+
+        let trade_msg = QueryMsg::GetTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &trade_msg)
+            .unwrap();
+        assert_eq!(res.trade.unwrap().listing_fee, Uint128::new(10));
+
+        // Refunded when the buyer cancels
+        let cancel_by_buyer = ExecuteMsg::CancelTrade {
+            buyer: Some(buyer.to_string()),
+            seller: None,
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_by_buyer, &[])
+            .unwrap();
+        assert_eq!(listing_fee_outcome(&res), Some("refunded".to_string()));
+        assert_eq!(balance(&router, seller.as_str()), 2000);
+        assert_eq!(balance(&router, trade_contract.as_str()), 0);
+
+        // Forfeited when the seller cancels
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_trade,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let cancel_by_seller = ExecuteMsg::CancelTrade {
+            buyer: None,
+            seller: Some(seller.to_string()),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let res = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &cancel_by_seller, &[])
+            .unwrap();
+        assert_eq!(listing_fee_outcome(&res), Some("forfeited".to_string()));
+        assert_eq!(balance(&router, seller.as_str()), 1990);
+        assert_eq!(balance(&router, "fee_admin"), 10);
+
+        // Released to the fee admin when the trade executes
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &collection,
+            &seller,
+            &buyer,
+            TOKEN_ID,
+        );
+        assert_eq!(balance(&router, trade_contract.as_str()), 10);
+        let res = router
+            .execute_contract(
+                buyer.clone(),
+                trade_contract.clone(),
+                &execute_trade,
+                &coins(1015, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(listing_fee_outcome(&res), Some("released".to_string()));
+        assert_eq!(balance(&router, "fee_admin"), 20);
+        assert_eq!(balance(&router, trade_contract.as_str()), 0);
+        // seller get 985 from buyer and paid 2 listing fees of 10
+        assert_eq!(balance(&router, seller.as_str()), 2965);
+
+        // Refunded when an expired trade is created again under the same key
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+        let create_trade = |expires_at| ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let expires_at = Expiration::AtHeight(router.block_info().height + 1);
+        router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade(Some(expires_at)),
+                &coins(10, NATIVE_DENOM),
+            )
+            .unwrap();
+        router.update_block(|block| block.height += 1);
+        let res = router
+            .execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade(None),
+                &coins(10, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(listing_fee_outcome(&res), Some("refunded".to_string()));
+        assert_eq!(balance(&router, seller.as_str()), 2955);
+        assert_eq!(balance(&router, trade_contract.as_str()), 10);
+
+        // the contract holds nothing once the new trade ends
+        let cancel_by_seller = ExecuteMsg::CancelTrade {
+            buyer: None,
+            seller: Some(seller.to_string()),
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        let res = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &cancel_by_seller, &[])
+            .unwrap();
+        assert_eq!(listing_fee_outcome(&res), Some("forfeited".to_string()));
+        assert_eq!(balance(&router, trade_contract.as_str()), 0);
+    }
+
+    // a 1.0.0 deployment: no cw2 version, its config and one trade in the 1.0.0 layout
+    fn instantiate_v1_0_0(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: crate::msg::InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        deps.storage.set(
+            b"config",
+            br#"{"admin":"admin","fee_admin":"fee_admin","commission_addr":"commission_addr","seller_fee":"0.015","buyer_fee":"0.015","listing_fee":"10","e_break":false}"#,
+        );
+        let key = trade_key(&Addr::unchecked("buyer"), &Addr::unchecked("collection"), "1".to_string());
+        deps.storage.set(
+            &crate::state::trades().key(key),
+            br#"{"seller":"seller","buyer":"buyer","price":{"denom":"uluna","amount":"1000"},"nft_collection":"collection","nft_id":"1","is_confirmed_trade":true,"seller_fee":"0.015","buyer_fee":"0.015"}"#,
+        );
+        Ok(Response::new())
+    }
+
+    // nftswitch claiming a release newer than the code under test
+    fn instantiate_future_version(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: crate::msg::InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        let res = crate::contract::instantiate(deps.branch(), env, info, msg)?;
+        cw2::set_contract_version(deps.storage, "crates.io:nftswitch", "99.0.0")?;
+        Ok(res)
+    }
+
+    #[test]
+    fn try_migrate_contract() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, _, _) = setup_accounts(&mut router).unwrap();
+
+        let trade_id = router.store_code(contract_trade());
+        let msg = crate::msg::InstantiateMsg {
+            admin: admin.to_string(),
+            fee_admin: "fee_admin".to_string(),
+            commission_addr: "commission_addr".to_string(),
+            buyer_fee: Decimal::from_ratio(15u128, 1000u128),
+            seller_fee: Decimal::from_ratio(15u128, 1000u128),
+            listing_fees: vec![coin(10, NATIVE_DENOM)],
+            cw20_listing_fees: vec![],
+            fee_admin_pubkey: None,
+        };
+        let instantiate_with = |router: &mut App, code_id: u64| {
+            router
+                .instantiate_contract(
+                    code_id,
+                    admin.clone(),
+                    &msg,
+                    &[],
+                    "nftswitch",
+                    Some(admin.to_string()),
+                )
+                .unwrap()
+        };
+        let query_version = |router: &App, contract: &Addr| -> ContractVersion {
+            router
+                .wrap()
+                .query_wasm_smart(contract.clone(), &QueryMsg::GetContractVersion {})
+                .unwrap()
+        };
+        let migrate_msg = crate::msg::MigrateMsg {};
+
+        let current = ContractVersion {
+            contract: "crates.io:nftswitch".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        let trade_contract = instantiate_with(&mut router, trade_id);
+        assert_eq!(query_version(&router, &trade_contract), current);
+
+        // Migrating to the same version is allowed
+        let res = router.migrate_contract(admin.clone(), trade_contract.clone(), &migrate_msg, trade_id);
+        assert!(res.is_ok());
+
+        // 1.0.0 deployments are rewritten in the current layout and get a version
+        let legacy_id = router.store_code(Box::new(
+            ContractWrapper::new(
+                crate::contract::execute,
+                instantiate_v1_0_0,
+                crate::contract::query,
+            )
+            .with_migrate(crate::contract::migrate),
+        ));
+        let legacy = instantiate_with(&mut router, legacy_id);
+        let res = router.migrate_contract(admin.clone(), legacy.clone(), &migrate_msg, trade_id);
+        assert!(res.is_ok());
+        assert_eq!(query_version(&router, &legacy), current);
+
+        let config: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(legacy.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.admin, Addr::unchecked("admin"));
+        assert_eq!(config.listing_fees, vec![coin(10, "uluna")]);
+        assert_eq!(config.buyer_fee, Decimal::from_ratio(15u128, 1000u128));
+
+        // the trade reads back priced natively, indexed and holding its token
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(
+                legacy.clone(),
+                &QueryMsg::GetTradesBySeller {
+                    seller: Addr::unchecked("seller"),
+                    limit: None,
+                    include_expired: None,
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.trades.len(), 1);
+        let trade = &res.trades[0];
+        assert_eq!(trade.price, TradePrice::Native(coin(1000, "uluna")));
+        assert!(trade.is_confirmed_trade);
+        assert_eq!(trade.expires_at, Expiration::Never {});
+        let res: TradeResponse = router
+            .wrap()
+            .query_wasm_smart(
+                legacy.clone(),
+                &QueryMsg::GetTokenReservation {
+                    nft_collection: "collection".to_string(),
+                    nft_id: "1".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.trade.as_ref(), Some(trade));
+
+        // Should error on a downgrade
+        let future_id = router.store_code(Box::new(ContractWrapper::new(
+            crate::contract::execute,
+            instantiate_future_version,
+            crate::contract::query,
+        )));
+        let future = instantiate_with(&mut router, future_id);
+        let err = router
+            .migrate_contract(admin.clone(), future, &migrate_msg, trade_id)
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidContractVersion {}));
+
+        // Should error migrating another contract to nftswitch
+        let cw20_id = router.store_code(contract_cw20());
+        let cw20_msg = cw20_base::msg::InstantiateMsg {
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: None,
+            marketing: None,
+        };
+        let token = router
+            .instantiate_contract(cw20_id, admin.clone(), &cw20_msg, &[], "token", Some(admin.to_string()))
+            .unwrap();
+        let err = router
+            .migrate_contract(admin.clone(), token, &migrate_msg, trade_id)
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidContractVersion {}));
+    }
+
+    #[test]
+    fn try_paginate_trades_and_listings() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+
+        let token_ids = [TOKEN_ID, TOKEN_ID + 1, TOKEN_ID + 2];
+        for token_id in token_ids {
+            mint_for(&mut router, &admin, &seller, &collection, token_id);
+            approve(&mut router, &seller, &collection, &trade_contract, token_id);
+            let create_trade = ExecuteMsg::CreateTrade {
+                nft_addr: collection.to_string(),
+                nft_id: token_id.to_string(),
+                buyer_addr: buyer.to_string(),
+                sale_price: coin(1000, NATIVE_DENOM).into(),
+                expires_at: None,
+                escrow_nft: None,
+                on_behalf_of: None,
+            };
+            let res = router.execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade,
+                &coins(10, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+
+        // Walk the seller's trades two at a time
+        let query_by_seller = |start_after: Option<TradeOffset>| QueryMsg::GetTradesBySeller {
+            seller: seller.clone(),
+            limit: Some(2),
+            include_expired: None,
+            start_after,
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller(None))
+            .unwrap();
+        assert_eq!(res.trades.len(), 2);
+        assert_eq!(
+            res.next,
+            Some(TradeOffset::new(
+                buyer.to_string(),
+                collection.to_string(),
+                res.trades[1].nft_id.clone(),
+            ))
+        );
+        let mut seen: Vec<String> = res.trades.iter().map(|t| t.nft_id.clone()).collect();
+
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller(res.next))
+            .unwrap();
+        assert_eq!(res.trades.len(), 1);
+        assert_eq!(res.next, None);
+        seen.extend(res.trades.iter().map(|t| t.nft_id.clone()));
+        seen.sort();
+        let mut expected: Vec<String> = token_ids.iter().map(|id| id.to_string()).collect();
+        expected.sort();
+        assert_eq!(seen, expected);
+
+        // The buyer side pages the same way
+        let query_by_buyer = QueryMsg::GetTradesByBuyer {
+            buyer: buyer.clone(),
+            limit: Some(1),
+            include_expired: None,
+            start_after: Some(TradeOffset::new(
+                buyer.to_string(),
+                collection.to_string(),
+                seen[1].clone(),
+            )),
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_buyer)
+            .unwrap();
+        assert_eq!(res.trades[0].nft_id, seen[2]);
+        assert!(res.next.is_some());
+
+        // Listings continue after the given token
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 3);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 4);
+        for token_id in [TOKEN_ID + 3, TOKEN_ID + 4] {
+            approve(&mut router, &seller, &collection, &trade_contract, token_id);
+            let create_listing = ExecuteMsg::CreateListing {
+                nft_addr: collection.to_string(),
+                nft_id: token_id.to_string(),
+                sale_price: coin(1000, NATIVE_DENOM).into(),
+            };
+            let res = router.execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_listing,
+                &coins(10, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+        let query_listings = QueryMsg::GetListingsBySeller {
+            seller: seller.clone(),
+            limit: None,
+            start_after: Some(ListingOffset {
+                nft_collection: collection.to_string(),
+                nft_id: (TOKEN_ID + 3).to_string(),
+            }),
+        };
+        let res: ListingsResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_listings)
+            .unwrap();
+        assert_eq!(res.listings.len(), 1);
+        assert_eq!(res.listings[0].nft_id, (TOKEN_ID + 4).to_string());
+    }
+
+    #[test]
+    fn try_query_trades_by_collection_and_all_trades() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        let cw721_id = router.store_code(contract_nft());
+        let msg = Cw721InstantiateMsg {
+            name: String::from("Other punk"),
+            symbol: String::from("OP"),
+            minter: admin.to_string(),
+        };
+        let other_collection = router
+            .instantiate_contract(cw721_id, admin.clone(), &msg, &[], "other", None)
+            .unwrap();
+
+        // two trades in the collection at 1000 and 500, one in the other collection at 200
+        for (nft_collection, token_id, price) in [
+            (&collection, TOKEN_ID, 1000u128),
+            (&collection, TOKEN_ID + 1, 500),
+            (&other_collection, TOKEN_ID, 200),
+        ] {
+            mint_for(&mut router, &admin, &seller, nft_collection, token_id);
+            approve(&mut router, &seller, nft_collection, &trade_contract, token_id);
+            let create_trade = ExecuteMsg::CreateTrade {
+                nft_addr: nft_collection.to_string(),
+                nft_id: token_id.to_string(),
+                buyer_addr: buyer.to_string(),
+                sale_price: coin(price, NATIVE_DENOM).into(),
+                expires_at: None,
+                escrow_nft: None,
+                on_behalf_of: None,
+            };
+            let res = router.execute_contract(
+                seller.clone(),
+                trade_contract.clone(),
+                &create_trade,
+                &coins(10, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin"),
+            trade_contract.clone(),
+            &confirm_trade,
+            &[],
+        );
+        assert!(res.is_ok());
+
+        let query_by_collection = |filter: Option<TradeFilter>| QueryMsg::GetTradesByCollection {
+            collection: collection.clone(),
+            limit: None,
+            include_expired: None,
+            start_after: None,
+            filter,
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection(None))
+            .unwrap();
+        assert_eq!(res.trades.len(), 2);
+        assert!(res.trades.iter().all(|trade| trade.nft_collection == collection));
+
+        let unconfirmed = TradeFilter {
+            confirmed: Some(false),
+            ..TradeFilter::default()
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection(Some(unconfirmed)))
+            .unwrap();
+        assert_eq!(res.trades.len(), 1);
+        assert_eq!(res.trades[0].nft_id, (TOKEN_ID + 1).to_string());
+
+        let price_range = TradeFilter {
+            denom: Some(NATIVE_DENOM.to_string()),
+            min_price: Some(Uint128::new(600)),
+            max_price: Some(Uint128::new(1000)),
+            ..TradeFilter::default()
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection(Some(price_range)))
+            .unwrap();
+        assert_eq!(res.trades.len(), 1);
+        assert!(res.trades[0].is_confirmed_trade);
+
+        // All trades, across collections, one page at a time
+        let query_all = |start_after: Option<TradeOffset>, filter: Option<TradeFilter>| {
+            QueryMsg::GetAllTrades {
+                limit: Some(2),
+                include_expired: None,
+                start_after,
+                filter,
+            }
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_all(None, None))
+            .unwrap();
+        assert_eq!(res.trades.len(), 2);
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_all(res.next, None))
+            .unwrap();
+        assert_eq!(res.trades.len(), 1);
+        assert_eq!(res.next, None);
+
+        // amounts in different denoms don't compare
+        let cheap = TradeFilter {
+            max_price: Some(Uint128::new(500)),
+            ..TradeFilter::default()
+        };
+        let err = router
+            .wrap()
+            .query_wasm_smart::<TradesResponse>(trade_contract.clone(), &query_all(None, Some(cheap.clone())))
+            .unwrap_err();
+        assert!(err.to_string().contains("A price bound needs a denom"));
+
+        let cheap = TradeFilter {
+            denom: Some(NATIVE_DENOM.to_string()),
+            ..cheap
+        };
+        let res: TradesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_all(None, Some(cheap)))
+            .unwrap();
+        assert_eq!(res.trades.len(), 2);
+        assert!(res.trades.iter().all(|trade| trade.price.amount() <= Uint128::new(500)));
+    }
+
+    #[test]
+    fn try_record_sale_history() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+
+        // seller sells to buyer, then buyer resells to admin
+        for (from, to) in [(&seller, &buyer), (&buyer, &admin)] {
+            approve(&mut router, from, &collection, &trade_contract, TOKEN_ID);
+            let execute_trade =
+                confirmed_trade(&mut router, &trade_contract, &collection, from, to, TOKEN_ID);
+            let res = router.execute_contract(
+                to.clone(),
+                trade_contract.clone(),
+                &execute_trade,
+                &coins(1015, NATIVE_DENOM),
+            );
+            assert!(res.is_ok());
+        }
+        assert_eq!(owner_of(&router, &collection, TOKEN_ID), admin.to_string());
+
+        let res: SaleResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetSale { id: 1 })
+            .unwrap();
+        let sale = res.sale.unwrap();
+        assert_eq!(sale.seller, seller);
+        assert_eq!(sale.buyer, buyer);
+        assert_eq!(sale.price, coin(1000, NATIVE_DENOM).into());
+        assert_eq!(sale.buyer_fee, Uint128::new(15));
+        assert_eq!(sale.seller_fee, Uint128::new(15));
+        assert_eq!(sale.royalty, Uint128::zero());
+        assert_eq!(sale.timestamp, router.block_info().time);
+
+        // Provenance lists both sales in order
+        let query_by_token = |start_after: Option<u64>| QueryMsg::GetSalesByToken {
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            limit: None,
+            start_after,
+        };
+        let res: SalesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_token(None))
+            .unwrap();
+        let ids: Vec<u64> = res.sales.iter().map(|sale| sale.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(res.sales[1].seller, buyer);
+        assert_eq!(res.sales[1].buyer, admin);
+
+        let res: SalesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_token(Some(1)))
+            .unwrap();
+        assert_eq!(res.sales.len(), 1);
+        assert_eq!(res.sales[0].id, 2);
+
+        let query_by_collection = QueryMsg::GetSalesByCollection {
+            collection: collection.clone(),
+            limit: Some(1),
+            start_after: None,
+        };
+        let res: SalesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_collection)
+            .unwrap();
+        assert_eq!(res.sales.len(), 1);
+        assert_eq!(res.sales[0].id, 1);
+
+        // buyer appears once on each side
+        let query_by_buyer = QueryMsg::GetSalesByBuyer {
+            buyer: buyer.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: SalesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_buyer)
+            .unwrap();
+        assert_eq!(res.sales.len(), 1);
+        assert_eq!(res.sales[0].id, 1);
+        let query_by_seller = QueryMsg::GetSalesBySeller {
+            seller: buyer.clone(),
+            limit: None,
+            start_after: None,
+        };
+        let res: SalesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_by_seller)
+            .unwrap();
+        assert_eq!(res.sales.len(), 1);
+        assert_eq!(res.sales[0].id, 2);
+    }
+
+    #[test]
+    fn try_track_trading_stats() {
+        let mut router = custom_mock_app();
+
+        // Setup intial accounts
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+
+        // Instantiate and configure contracts
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        setup_disburse(&mut router, &admin, &trade_contract);
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID + 1);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID + 1);
+
+        // a trade at 1000, then a listing at 500
+        let execute_trade = confirmed_trade(
+            &mut router,
+            &trade_contract,
+            &collection,
+            &seller,
+            &buyer,
+            TOKEN_ID,
+        );
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_trade,
+            &coins(1015, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let create_listing = ExecuteMsg::CreateListing {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            sale_price: coin(500, NATIVE_DENOM).into(),
+        };
+        let res = router.execute_contract(
+            seller.clone(),
+            trade_contract.clone(),
+            &create_listing,
+            &coins(10, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+        let confirm_listing = ExecuteMsg::ConfirmListing {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            seller_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            buyer_fee_pct: Decimal::from_ratio(15u128, 1000u128),
+            is_confirmed_by_fee_admin: true,
+        };
+        let res = router.execute_contract(
+            Addr::unchecked("fee_admin"),
+            trade_contract.clone(),
+            &confirm_listing,
+            &[],
+        );
+        assert!(res.is_ok());
+        let execute_listing = ExecuteMsg::ExecuteListing {
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        let res = router.execute_contract(
+            buyer.clone(),
+            trade_contract.clone(),
+            &execute_listing,
+            &coins(507, NATIVE_DENOM),
+        );
+        assert!(res.is_ok());
+
+        let expected = vec![TradeStats {
+            denom: NATIVE_DENOM.to_string(),
+            trade_count: 2,
+            volume: Uint128::new(1500),
+            last_price: Uint128::new(500),
+            high_price: Uint128::new(1000),
+            low_price: Uint128::new(500),
+        }];
+        for query in [
+            QueryMsg::GetGlobalStats {},
+            QueryMsg::GetCollectionStats {
+                collection: collection.to_string(),
+            },
+            QueryMsg::GetSellerStats {
+                seller: seller.to_string(),
+            },
+            QueryMsg::GetBuyerStats {
+                buyer: buyer.to_string(),
+            },
+        ] {
+            let res: StatsResponse = router
+                .wrap()
+                .query_wasm_smart(trade_contract.clone(), &query)
+                .unwrap();
+            assert_eq!(res.stats, expected);
+        }
+
+        // the seller never bought anything
+        let res: StatsResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetBuyerStats {
+                    buyer: seller.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(res.stats.is_empty());
+    }
+
+    #[test]
+    fn try_two_step_admin_transfer() {
+        let mut router = custom_mock_app();
+
+        let (admin, seller, _) = setup_accounts(&mut router).unwrap();
+        let (trade_contract, _) = setup_contract(&mut router, &admin).unwrap();
+        let new_admin = Addr::unchecked("new_admin");
+        let config = |router: &App| -> ConfigResponse {
+            router
+                .wrap()
+                .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetConfig {})
+                .unwrap()
+        };
+
+        // only the admin proposes
+        let propose = |expires_at| ExecuteMsg::ProposeAdmin {
+            new_admin: new_admin.to_string(),
+            expires_at,
+        };
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &propose(None), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // nothing to accept yet
+        let err = router
+            .execute_contract(new_admin.clone(), trade_contract.clone(), &ExecuteMsg::AcceptAdmin {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::NoPendingTransfer {}));
+
+        // an expired proposal can not be accepted
+        let expires_at = Expiration::AtHeight(router.block_info().height + 10);
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &propose(Some(expires_at)), &[])
+            .unwrap();
+        let pending = config(&router).pending_admin.unwrap();
+        assert_eq!(pending.new_address, new_admin);
+        assert_eq!(pending.expires_at, expires_at);
+        router.update_block(|block| block.height += 10);
+        let err = router
+            .execute_contract(new_admin.clone(), trade_contract.clone(), &ExecuteMsg::AcceptAdmin {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::TransferExpired {}));
+
+        // cancelling clears the proposal
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &propose(None), &[])
+            .unwrap();
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &ExecuteMsg::CancelAdminProposal {}, &[])
+            .unwrap();
+        assert_eq!(config(&router).pending_admin, None);
+
+        // only the proposed address accepts
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &propose(None), &[])
+            .unwrap();
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &ExecuteMsg::AcceptAdmin {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+        router
+            .execute_contract(new_admin.clone(), trade_contract.clone(), &ExecuteMsg::AcceptAdmin {}, &[])
+            .unwrap();
+        let cfg = config(&router);
+        assert_eq!(cfg.admin, new_admin);
+        assert_eq!(cfg.pending_admin, None);
+
+        // the old admin is locked out
+        let err = router
+            .execute_contract(admin.clone(), trade_contract.clone(), &ExecuteMsg::RenounceAdmin {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // renouncing leaves no one able to administer
+        router
+            .execute_contract(new_admin.clone(), trade_contract.clone(), &ExecuteMsg::RenounceAdmin {}, &[])
+            .unwrap();
+        assert_eq!(config(&router).admin, trade_contract);
+        let err = router
+            .execute_contract(new_admin.clone(), trade_contract.clone(), &propose(None), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn try_role_based_access() {
+        let mut router = custom_mock_app();
+
+        let (admin, seller, _) = setup_accounts(&mut router).unwrap();
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        let bot = Addr::unchecked("ops_bot");
+        let has_role = |router: &App, role: Role, address: &Addr| -> bool {
+            let res: HasRoleResponse = router
+                .wrap()
+                .query_wasm_smart(
+                    trade_contract.clone(),
+                    &QueryMsg::HasRole {
+                        role,
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.has_role
+        };
+        let update = |buyer_fee: Option<Decimal>, e_break: Option<bool>| ExecuteMsg::UpdateConfig {
+            commission_addr: None,
+            buyer_fee,
+            seller_fee: None,
+            listing_fees: None,
+            cw20_listing_fees: None,
+            fee_admin_pubkey: None,
+            e_break,
+        };
+
+        // the admin holds every role, the fee admin confirms trades
+        assert!(has_role(&router, Role::Pauser, &admin));
+        assert!(has_role(&router, Role::CollectionCurator, &admin));
+        assert!(has_role(&router, Role::TradeConfirmer, &Addr::unchecked("fee_admin")));
+        assert!(!has_role(&router, Role::FeeSetter, &Addr::unchecked("fee_admin")));
+
+        // only the admin grants roles
+        let grant = |role| ExecuteMsg::GrantRole {
+            role,
+            address: bot.to_string(),
+        };
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &grant(Role::Pauser), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &grant(Role::Pauser), &[])
+            .unwrap();
+        let res: RoleMembersResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetRoleMembers {
+                    role: Role::Pauser,
+                    limit: None,
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.members, vec![bot.clone()]);
+
+        // a pauser flips the break but can not touch fees or curation
+        router
+            .execute_contract(bot.clone(), trade_contract.clone(), &update(None, Some(true)), &[])
+            .unwrap();
+        let cfg: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert!(cfg.e_break);
+        let fee = Some(Decimal::percent(2));
+        let err = router
+            .execute_contract(bot.clone(), trade_contract.clone(), &update(fee, None), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+        let set_policy = ExecuteMsg::SetRoyaltyPolicy {
+            collection: collection.to_string(),
+            policy: RoyaltyPolicy::Ignore,
+        };
+        let err = router
+            .execute_contract(bot.clone(), trade_contract.clone(), &set_policy, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // further roles open the matching actions
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &grant(Role::FeeSetter), &[])
+            .unwrap();
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &grant(Role::CollectionCurator), &[])
+            .unwrap();
+        router
+            .execute_contract(bot.clone(), trade_contract.clone(), &update(fee, None), &[])
+            .unwrap();
+        router
+            .execute_contract(bot.clone(), trade_contract.clone(), &set_policy, &[])
+            .unwrap();
+
+        // revoking takes the right away again
+        let revoke = ExecuteMsg::RevokeRole {
+            role: Role::Pauser,
+            address: bot.to_string(),
+        };
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &revoke, &[])
+            .unwrap();
+        assert!(!has_role(&router, Role::Pauser, &bot));
+        let err = router
+            .execute_contract(bot.clone(), trade_contract.clone(), &update(None, Some(false)), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn try_granular_pauses() {
+        let mut router = custom_mock_app();
+
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+        for token_id in TOKEN_ID..TOKEN_ID + 2 {
+            mint_for(&mut router, &admin, &seller, &collection, token_id);
+            approve(&mut router, &seller, &collection, &trade_contract, token_id);
+        }
+        let execute_trade = confirmed_trade(&mut router, &trade_contract, &collection, &seller, &buyer, TOKEN_ID);
+        let cancel_trade = ExecuteMsg::CancelTrade {
+            buyer: Some(buyer.to_string()),
+            seller: None,
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+        };
+        let set_pause = |action, paused, collection: Option<&Addr>| ExecuteMsg::SetPause {
+            action,
+            paused,
+            collection: collection.map(|c| c.to_string()),
+        };
+        let set_e_break = |e_break| ExecuteMsg::UpdateConfig {
+            commission_addr: None,
+            buyer_fee: None,
+            seller_fee: None,
+            listing_fees: None,
+            cw20_listing_fees: None,
+            fee_admin_pubkey: None,
+            e_break: Some(e_break),
+        };
+
+        // only pausers pause
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &set_pause(PauseAction::Create, true, None), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+
+        // the emergency break stops executions but not cancellations
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_e_break(true), &[])
+            .unwrap();
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &execute_trade, &coins(1015, NATIVE_DENOM))
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::EmergencyBreakActivated {}));
+        router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_trade, &[])
+            .unwrap();
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_e_break(false), &[])
+            .unwrap();
+
+        // a global create pause blocks new trades until lifted
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(PauseAction::Create, true, None), &[])
+            .unwrap();
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &create_trade, &coins(10, NATIVE_DENOM))
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::ActionPaused { action } if action == "create"
+        ));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(PauseAction::Create, false, None), &[])
+            .unwrap();
+
+        // collection pauses only touch the paused step
+        let execute_trade =
+            confirmed_trade(&mut router, &trade_contract, &collection, &seller, &buyer, TOKEN_ID + 1);
+        router
+            .execute_contract(
+                admin.clone(),
+                trade_contract.clone(),
+                &set_pause(PauseAction::Execute, true, Some(&collection)),
+                &[],
+            )
+            .unwrap();
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &execute_trade, &coins(1015, NATIVE_DENOM))
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::CollectionPaused { .. }));
+
+        // the matrix reports every switch
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(PauseAction::Cancel, true, None), &[])
+            .unwrap();
+        let matrix: PauseMatrixResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetPauseMatrix {
+                    limit: None,
+                    start_after: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            matrix,
+            PauseMatrixResponse {
+                e_break: false,
+                global: Pauses {
+                    cancel: true,
+                    ..Pauses::default()
+                },
+                collections: vec![CollectionPauses {
+                    collection: collection.clone(),
+                    pauses: Pauses {
+                        execute: true,
+                        ..Pauses::default()
+                    },
+                }],
+            }
+        );
+
+        // cancellations only stop on their own switch
+        let cancel_trade = ExecuteMsg::CancelTrade {
+            buyer: Some(buyer.to_string()),
+            seller: None,
+            nft_collection: collection.to_string(),
+            nft_id: (TOKEN_ID + 1).to_string(),
+        };
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_trade, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::ActionPaused { .. }));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_pause(PauseAction::Cancel, false, None), &[])
+            .unwrap();
+        router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_trade, &[])
+            .unwrap();
+    }
+
+    #[test]
+    fn try_fee_caps_and_timelock() {
+        let mut router = custom_mock_app();
+
+        let (admin, seller, buyer) = setup_accounts(&mut router).unwrap();
+        let (trade_contract, collection) = setup_contract(&mut router, &admin).unwrap();
+
+        // instantiate checks and stores each fee on its own side
+        let code_id = router.store_code(contract_trade());
+        let mut msg = crate::msg::InstantiateMsg {
+            admin: "admin".to_string(),
+            fee_admin: "fee_admin".to_string(),
+            commission_addr: "commission_addr".to_string(),
+            buyer_fee: Decimal::percent(11),
+            seller_fee: Decimal::percent(1),
+            listing_fees: vec![coin(10, NATIVE_DENOM)],
+            cw20_listing_fees: vec![],
+            fee_admin_pubkey: None,
+        };
+        let err = router
+            .instantiate_contract(code_id, admin.clone(), &msg, &[], "capped", None)
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::BuyerFeeAboveCap { .. }));
+        msg.buyer_fee = Decimal::percent(2);
+        let other_contract = router
+            .instantiate_contract(code_id, admin.clone(), &msg, &[], "capped", None)
+            .unwrap();
+        let cfg: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(other_contract, &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(cfg.buyer_fee, Decimal::percent(2));
+        assert_eq!(cfg.seller_fee, Decimal::percent(1));
+        let update_fees = |buyer_fee: Option<Decimal>, seller_fee: Option<Decimal>| ExecuteMsg::UpdateConfig {
+            commission_addr: None,
+            buyer_fee,
+            seller_fee,
+            listing_fees: None,
+            cw20_listing_fees: None,
+            fee_admin_pubkey: None,
+            e_break: None,
+        };
+        let pending = |router: &App| -> PendingFeeChangeResponse {
+            router
+                .wrap()
+                .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetPendingFeeChange {})
+                .unwrap()
+        };
+
+        // fees above the default 10% caps are rejected wherever they are set
+        let err = router
+            .execute_contract(admin.clone(), trade_contract.clone(), &update_fees(Some(Decimal::percent(11)), None), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::BuyerFeeAboveCap { .. }));
+        let set_collection_fees = ExecuteMsg::SetCollectionFees {
+            collection: collection.to_string(),
+            buyer_fee: Decimal::percent(1),
+            seller_fee: Decimal::one(),
+            partner: false,
+        };
+        let err = router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_collection_fees, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::SellerFeeAboveCap { .. }));
+
+        mint_for(&mut router, &admin, &seller, &collection, TOKEN_ID);
+        approve(&mut router, &seller, &collection, &trade_contract, TOKEN_ID);
+        let create_trade = ExecuteMsg::CreateTrade {
+            nft_addr: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            buyer_addr: buyer.to_string(),
+            sale_price: coin(1000, NATIVE_DENOM).into(),
+            expires_at: None,
+            escrow_nft: None,
+            on_behalf_of: None,
+        };
+        router
+            .execute_contract(seller.clone(), trade_contract.clone(), &create_trade, &coins(10, NATIVE_DENOM))
+            .unwrap();
+        let confirm_trade = ExecuteMsg::ConfirmTrade {
+            buyer: buyer.to_string(),
+            nft_collection: collection.to_string(),
+            nft_id: TOKEN_ID.to_string(),
+            seller_fee_pct: Decimal::percent(50),
+            buyer_fee_pct: Decimal::percent(1),
+            is_confirmed_by_fee_admin: true,
+        };
+        let err = router
+            .execute_contract(Addr::unchecked("fee_admin"), trade_contract.clone(), &confirm_trade, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::SellerFeeAboveCap { .. }));
+
+        // caps must stay below 100% and above the fees in force
+        let set_bounds = |max_buyer_fee, max_seller_fee| ExecuteMsg::SetFeeBounds {
+            max_buyer_fee,
+            max_seller_fee,
+            increase_delay: 3600,
+        };
+        for (max_buyer_fee, max_seller_fee) in [
+            (Decimal::one(), Decimal::percent(5)),
+            (Decimal::percent(5), Decimal::percent(1)),
+        ] {
+            let err = router
+                .execute_contract(admin.clone(), trade_contract.clone(), &set_bounds(max_buyer_fee, max_seller_fee), &[])
+                .unwrap_err();
+            assert!(matches!(err.downcast().unwrap(), ContractError::InvalidFeeCap {}));
+        }
+        let err = router
+            .execute_contract(seller.clone(), trade_contract.clone(), &set_bounds(Decimal::percent(5), Decimal::percent(5)), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_bounds(Decimal::percent(5), Decimal::percent(5)), &[])
+            .unwrap();
+        let bounds: FeeBounds = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetFeeBounds {})
+            .unwrap();
+        assert_eq!(bounds.increase_delay, 3600);
+
+        // cuts apply at once, increases are scheduled and visible before they apply
+        router
+            .execute_contract(
+                admin.clone(),
+                trade_contract.clone(),
+                &update_fees(Some(Decimal::percent(4)), Some(Decimal::percent(1))),
+                &[],
+            )
+            .unwrap();
+        let cfg: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(cfg.seller_fee, Decimal::percent(1));
+        assert_eq!(cfg.buyer_fee, Decimal::from_ratio(15u128, 1000u128));
+        let change = pending(&router).change.unwrap();
+        assert_eq!(change.buyer_fee, Some(Decimal::percent(4)));
+        assert_eq!(change.seller_fee, None);
+        assert_eq!(change.effective_at, router.block_info().time.plus_seconds(3600));
+
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &ExecuteMsg::ApplyFeeChange {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::FeeChangeLocked { .. }));
+
+        // a fee setter can call the increase off
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &ExecuteMsg::CancelFeeChange {}, &[])
+            .unwrap();
+        assert_eq!(pending(&router).change, None);
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &ExecuteMsg::ApplyFeeChange {}, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::NoPendingFeeChange {}));
+
+        // anyone applies a due increase
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &update_fees(Some(Decimal::percent(4)), None), &[])
+            .unwrap();
+        router.update_block(|block| block.time = block.time.plus_seconds(3600));
+        router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &ExecuteMsg::ApplyFeeChange {}, &[])
+            .unwrap();
+        let cfg: ConfigResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(cfg.buyer_fee, Decimal::percent(4));
+        assert_eq!(pending(&router).change, None);
+
+        // collection overrides above the fees in force wait out the same timelock
+        let set_collection_fees = ExecuteMsg::SetCollectionFees {
+            collection: collection.to_string(),
+            buyer_fee: Decimal::percent(5),
+            seller_fee: Decimal::percent(1),
+            partner: false,
+        };
+        router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_collection_fees, &[])
+            .unwrap();
+        let query_fees = QueryMsg::GetCollectionFees {
+            collection: collection.to_string(),
+        };
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert!(!res.is_override);
+        let res: PendingCollectionFeeChangeResponse = router
+            .wrap()
+            .query_wasm_smart(
+                trade_contract.clone(),
+                &QueryMsg::GetPendingCollectionFeeChange {
+                    collection: collection.to_string(),
+                },
+            )
+            .unwrap();
+        let change = res.change.unwrap();
+        assert_eq!(change.fees.unwrap().buyer_fee, Decimal::percent(5));
+        assert_eq!(change.effective_at, router.block_info().time.plus_seconds(3600));
+
+        let apply_change = ExecuteMsg::ApplyCollectionFeeChange {
+            collection: collection.to_string(),
+        };
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &apply_change, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::FeeChangeLocked { .. }));
+        let cancel_change = ExecuteMsg::CancelCollectionFeeChange {
+            collection: collection.to_string(),
+        };
+        let err = router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &cancel_change, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::Unauthorized {}));
+        router.update_block(|block| block.time = block.time.plus_seconds(3600));
+        router
+            .execute_contract(buyer.clone(), trade_contract.clone(), &apply_change, &[])
+            .unwrap();
+        let res: CollectionFeesResponse = router
+            .wrap()
+            .query_wasm_smart(trade_contract.clone(), &query_fees)
+            .unwrap();
+        assert!(res.is_override);
+        assert_eq!(res.fees.buyer_fee, Decimal::percent(5));
+
+        // caps may not strand a collection override either
+        let err = router
+            .execute_contract(admin.clone(), trade_contract.clone(), &set_bounds(Decimal::percent(4), Decimal::percent(5)), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast().unwrap(), ContractError::InvalidFeeCap {}));
+    }
 }
@@ -1,7 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
-use crate::state::{Trade};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
+use cw_utils::Expiration;
+use crate::state::{
+    CollectionOffer, FeeDiscount, FeeSchedule, Listing, NftItem, Offer, Operator, PauseAction, Pauses, PendingCollectionFeeChange, PendingFeeChange, PendingTransfer, Role, RoyaltyPolicy, Sale,
+    Trade, TradePrice, TradeStats,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -13,22 +18,61 @@ pub struct InstantiateMsg {
     pub commission_addr: String,
     pub buyer_fee: Decimal,
     pub seller_fee: Decimal,
-    pub listing_fee: Uint128
+    pub listing_fees: Vec<Coin>,
+    pub cw20_listing_fees: Vec<Cw20Coin>,
+    pub fee_admin_pubkey: Option<Binary>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    CreateTrade { nft_addr: String, nft_id: String, buyer_addr: String, sale_price: Coin},
+    CreateTrade {
+        nft_addr: String,
+        nft_id: String,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        expires_at: Option<Expiration>,
+        /// Hand the NFT to the contract now instead of keeping it under approval
+        escrow_nft: Option<bool>,
+        /// Seller an operator creates the trade for
+        on_behalf_of: Option<String>,
+    },
+    /// Trade NFTs for NFTs, optionally with coins added by the buyer (`sale_price`) or the seller
+    CreateSwap {
+        nft_addr: String,
+        nft_id: String,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        offered_nfts: Vec<NftItem>,
+        wanted_nfts: Vec<NftItem>,
+        seller_coins: Uint128,
+        on_behalf_of: Option<String>,
+    },
+    /// Sell several NFTs under one price, the first one keys the trade
+    CreateBundle {
+        nfts: Vec<NftItem>,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        on_behalf_of: Option<String>,
+    },
     CancelTrade { buyer: Option<String>, seller: Option<String>, nft_collection: String, nft_id: String },
-    ExecuteTrade { buyer: String, nft_collection: String, nft_id: String },
+    /// `fee_confirmation` stands in for `ConfirmTrade` when the trade is not confirmed yet
+    ExecuteTrade {
+        buyer: String,
+        nft_collection: String,
+        nft_id: String,
+        fee_confirmation: Option<SignedFeeConfirmation>,
+    },
+    /// Fee setter for the fee fields, pauser for `e_break`, admin only for the rest. `admin` and
+    /// `fee_admin` change hands through `ProposeAdmin` and `ProposeFeeAdmin`.
+    /// Fee cuts apply at once, increases replace any pending change and wait for `ApplyFeeChange`
     UpdateConfig {
-        admin: Option<String>,
-        fee_admin: Option<String>,
         commission_addr: Option<String>,
         buyer_fee: Option<Decimal>,
         seller_fee: Option<Decimal>,
-        listing_fee: Option<Uint128>,
+        listing_fees: Option<Vec<Coin>>,
+        cw20_listing_fees: Option<Vec<Cw20Coin>>,
+        fee_admin_pubkey: Option<Binary>,
         e_break: Option<bool>
     },
     ConfirmTrade {
@@ -38,22 +82,317 @@ pub enum ExecuteMsg {
         seller_fee_pct: Decimal,
         buyer_fee_pct: Decimal,
         is_confirmed_by_fee_admin: bool
-    }
+    },
+    /// Pay a listing fee or a trade in CW20 tokens, with a `ReceiveMsg` payload
+    Receive(Cw20ReceiveMsg),
+    /// List an NFT for any buyer at a fixed price
+    CreateListing { nft_addr: String, nft_id: String, sale_price: TradePrice },
+    CancelListing { nft_collection: String, nft_id: String },
+    ConfirmListing {
+        nft_collection: String,
+        nft_id: String,
+        seller_fee_pct: Decimal,
+        buyer_fee_pct: Decimal,
+        is_confirmed_by_fee_admin: bool
+    },
+    ExecuteListing { nft_collection: String, nft_id: String },
+    /// Bid on a token, escrowing the price plus the buyer fee
+    CreateOffer { nft_collection: String, nft_id: String, price: TradePrice },
+    WithdrawOffer { nft_collection: String, nft_id: String },
+    /// Sell the token to the bidder, as its current owner
+    AcceptOffer { nft_collection: String, nft_id: String, bidder: String },
+    /// Bid on any `quantity` tokens of a collection, escrowing the price plus the buyer fee for each
+    CreateCollectionOffer { nft_collection: String, price: TradePrice, quantity: u32 },
+    WithdrawCollectionOffer { nft_collection: String },
+    /// Sell one token of the collection to the bidder, as its current owner
+    AcceptCollectionOffer { nft_collection: String, nft_id: String, bidder: String },
+    /// Check up to `limit` trades after `start_after` and remove the expired ones, open to anyone.
+    /// A full page returns the key to continue from in the `next_*` attributes
+    PruneExpiredTrades {
+        limit: Option<u32>,
+        start_after: Option<TradeOffset>,
+    },
+    /// Let `operator` create, cancel and execute trades for the sender, optionally only until
+    /// `expires_at` and only on `collections`. Funds and NFTs still flow to and from the sender.
+    RegisterOperator {
+        operator: String,
+        expires_at: Option<Expiration>,
+        collections: Option<Vec<String>>,
+    },
+    RevokeOperator { operator: String },
+    /// Collection curator only, choose how trades in `collection` pay cw2981 royalties
+    SetRoyaltyPolicy { collection: String, policy: RoyaltyPolicy },
+    /// Fee setter only, override the fees of `collection`. Partnership fees must not exceed the
+    /// global ones. Fee cuts apply at once, increases wait for `ApplyCollectionFeeChange`
+    SetCollectionFees {
+        collection: String,
+        buyer_fee: Decimal,
+        seller_fee: Decimal,
+        partner: bool,
+    },
+    /// Fee setter only, return `collection` to the global fees, through the timelock when they
+    /// are higher
+    RemoveCollectionFees { collection: String },
+    /// Fee setter only, waive a share of the fees for holders of `membership_collection`, up to
+    /// `state::MAX_HOLDER_DISCOUNTS` collections. A collection that fails to answer the holder
+    /// query is skipped and grants nothing
+    SetHolderDiscount {
+        membership_collection: String,
+        buyer_discount: Decimal,
+        seller_discount: Decimal,
+    },
+    /// Fee setter only
+    RemoveHolderDiscount { membership_collection: String },
+    /// Admin only, `new_admin` takes over once it sends `AcceptAdmin`
+    ProposeAdmin {
+        new_admin: String,
+        expires_at: Option<Expiration>,
+    },
+    AcceptAdmin {},
+    /// Admin only
+    CancelAdminProposal {},
+    /// Admin only, hands administration to the contract itself. Can not be undone
+    RenounceAdmin {},
+    /// Admin only, `new_fee_admin` takes over once it sends `AcceptFeeAdmin`. Accepting clears
+    /// `fee_admin_pubkey`, the admin registers the new fee admin's key through `UpdateConfig`
+    ProposeFeeAdmin {
+        new_fee_admin: String,
+        expires_at: Option<Expiration>,
+    },
+    AcceptFeeAdmin {},
+    /// Admin only
+    CancelFeeAdminProposal {},
+    /// Admin only, `address` may act as `role` alongside the admin
+    GrantRole { role: Role, address: String },
+    /// Admin only
+    RevokeRole { role: Role, address: String },
+    /// Pauser only, pause or resume one trading step for everyone or for a single `collection`.
+    /// Withdrawing offers and pruning stay open whatever is paused
+    SetPause {
+        action: PauseAction,
+        paused: bool,
+        collection: Option<String>,
+    },
+    /// Admin only, caps every fee rate that can be set. `increase_delay` is in seconds
+    SetFeeBounds {
+        max_buyer_fee: Decimal,
+        max_seller_fee: Decimal,
+        increase_delay: u64,
+    },
+    /// Anyone, once the pending fee increase is due
+    ApplyFeeChange {},
+    /// Fee setter only
+    CancelFeeChange {},
+    /// Anyone, once the pending fee increase of `collection` is due
+    ApplyCollectionFeeChange { collection: String },
+    /// Fee setter only
+    CancelCollectionFeeChange { collection: String },
+}
+
+/// Fee rates the fee admin approves off-chain for a single trade
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeConfirmation {
+    pub seller_fee_pct: Decimal,
+    pub buyer_fee_pct: Decimal,
+    pub expires_at: Expiration,
+    /// Single use, a spent nonce can not be submitted again
+    pub nonce: u64,
+}
+
+/// A `FeeConfirmation` with the fee admin's secp256k1 signature over
+/// `helpers::fee_confirmation_payload`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedFeeConfirmation {
+    pub confirmation: FeeConfirmation,
+    pub signature: Binary,
+}
+
+/// Payload of a CW20 `Send` to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    CreateTrade {
+        nft_addr: String,
+        nft_id: String,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        expires_at: Option<Expiration>,
+        /// Hand the NFT to the contract now instead of keeping it under approval
+        escrow_nft: Option<bool>,
+        /// Seller an operator creates the trade for
+        on_behalf_of: Option<String>,
+    },
+    CreateSwap {
+        nft_addr: String,
+        nft_id: String,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        offered_nfts: Vec<NftItem>,
+        wanted_nfts: Vec<NftItem>,
+        seller_coins: Uint128,
+        on_behalf_of: Option<String>,
+    },
+    CreateBundle {
+        nfts: Vec<NftItem>,
+        buyer_addr: String,
+        sale_price: TradePrice,
+        on_behalf_of: Option<String>,
+    },
+    /// `fee_confirmation` stands in for `ConfirmTrade` when the trade is not confirmed yet
+    ExecuteTrade {
+        buyer: String,
+        nft_collection: String,
+        nft_id: String,
+        fee_confirmation: Option<SignedFeeConfirmation>,
+    },
+    CreateListing { nft_addr: String, nft_id: String, sale_price: TradePrice },
+    ExecuteListing { nft_collection: String, nft_id: String },
+    CreateOffer { nft_collection: String, nft_id: String, price: TradePrice },
+    CreateCollectionOffer { nft_collection: String, price: TradePrice, quantity: u32 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetConfig {},
-    // GetTrades {},
     GetTrade { buyer: String, nft_collection: String, nft_id: String  },
-    GetTradesByBuyer { buyer: Addr, limit: Option<u32> },
-    GetTradesBySeller { seller: Addr, limit: Option<u32> },
-    // GetAllTrades {},
+    /// Expired trades are skipped unless `include_expired` is set
+    GetTradesByBuyer {
+        buyer: Addr,
+        limit: Option<u32>,
+        include_expired: Option<bool>,
+        start_after: Option<TradeOffset>,
+    },
+    GetTradesBySeller {
+        seller: Addr,
+        limit: Option<u32>,
+        include_expired: Option<bool>,
+        start_after: Option<TradeOffset>,
+    },
+    GetTradesByCollection {
+        collection: Addr,
+        limit: Option<u32>,
+        include_expired: Option<bool>,
+        start_after: Option<TradeOffset>,
+        filter: Option<TradeFilter>,
+    },
+    GetAllTrades {
+        limit: Option<u32>,
+        include_expired: Option<bool>,
+        start_after: Option<TradeOffset>,
+        filter: Option<TradeFilter>,
+    },
+    /// Trade currently holding the token, if any
+    GetTokenReservation { nft_collection: String, nft_id: String },
+    GetListing { nft_collection: String, nft_id: String },
+    GetListingsByCollection {
+        collection: Addr,
+        limit: Option<u32>,
+        start_after: Option<ListingOffset>,
+    },
+    GetListingsBySeller {
+        seller: Addr,
+        limit: Option<u32>,
+        start_after: Option<ListingOffset>,
+    },
+    GetOffer { nft_collection: String, nft_id: String, bidder: String },
+    /// `start_after` is the bidder of the last offer returned
+    GetOffersByToken {
+        nft_collection: String,
+        nft_id: String,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetOffersByBidder {
+        bidder: Addr,
+        limit: Option<u32>,
+        start_after: Option<OfferOffset>,
+    },
+    GetOffersByCollection {
+        collection: Addr,
+        limit: Option<u32>,
+        start_after: Option<OfferOffset>,
+    },
+    GetCollectionOffer { nft_collection: String, bidder: String },
+    /// `start_after` is the bidder of the last offer returned
+    GetCollectionOffersByCollection {
+        collection: Addr,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    /// `start_after` is the collection of the last offer returned
+    GetCollectionOffersByBidder {
+        bidder: Addr,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetOperator { principal: String, operator: String },
+    /// `start_after` is the last operator returned
+    GetOperators {
+        principal: Addr,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    GetRoyaltyPolicy { collection: String },
+    /// Fees a new trade in `collection` would be created with
+    GetCollectionFees { collection: String },
+    /// Best discount `address` gets from the membership collections it holds, skipping those that
+    /// fail to answer
+    GetHolderDiscount { address: String },
+    /// cw2 name and version of the deployed code, returns `cw2::ContractVersion`
+    GetContractVersion {},
+    GetSale { id: u64 },
+    /// Sales are returned oldest first, `start_after` is the id of the last sale returned
+    GetSalesByCollection {
+        collection: Addr,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    /// Provenance of a single token
+    GetSalesByToken {
+        nft_collection: String,
+        nft_id: String,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    GetSalesByBuyer {
+        buyer: Addr,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    GetSalesBySeller {
+        seller: Addr,
+        limit: Option<u32>,
+        start_after: Option<u64>,
+    },
+    /// Executed trade aggregates, one entry per denom traded
+    GetGlobalStats {},
+    GetCollectionStats { collection: String },
+    GetSellerStats { seller: String },
+    GetBuyerStats { buyer: String },
+    /// Addresses granted `role`, the admin and fee admin hold theirs implicitly and are not listed
+    GetRoleMembers {
+        role: Role,
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    HasRole { role: Role, address: String },
+    /// Emergency break, global pauses and the collections with pauses, `start_after` is the last
+    /// collection returned
+    GetPauseMatrix {
+        limit: Option<u32>,
+        start_after: Option<String>,
+    },
+    /// Fee caps and the fee increase timelock, returns `state::FeeBounds`
+    GetFeeBounds {},
+    /// Global fee increase scheduled but not applied yet
+    GetPendingFeeChange {},
+    /// Fee increase of `collection` scheduled but not applied yet
+    GetPendingCollectionFeeChange { collection: String },
 }
 
 
-/// Offset for bid pagination
+/// Key of the last trade returned, to continue a trade query after it
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TradeOffset {
     pub buyer: String,
@@ -70,16 +409,151 @@ impl TradeOffset {
         }
     }
 }
+/// Narrows trade queries, every field that is set must match
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TradeFilter {
+    /// Only trades whose fees were (or were not) confirmed by the fee admin
+    pub confirmed: Option<bool>,
+    /// Only trades priced in this native denom or cw20 address
+    pub denom: Option<String>,
+    /// Inclusive bounds on the price amount, only accepted along with `denom`
+    pub min_price: Option<Uint128>,
+    pub max_price: Option<Uint128>,
+}
+
+/// Key of the last listing returned
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingOffset {
+    pub nft_collection: String,
+    pub nft_id: String,
+}
+
+/// Key of the last offer returned
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferOffset {
+    pub nft_collection: String,
+    pub nft_id: String,
+    pub bidder: String,
+}
+
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TradesResponse {
     pub trades: Vec<Trade>,
+    /// Pass as `start_after` to get the next page, None once every trade was returned. A query reads
+    /// at most 100 trades, so a page may come back short (even empty) with `next` still set.
+    pub next: Option<TradeOffset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub stats: Vec<TradeStats>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFeeChangeResponse {
+    pub change: Option<PendingFeeChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCollectionFeeChangeResponse {
+    pub change: Option<PendingCollectionFeeChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionPauses {
+    pub collection: Addr,
+    pub pauses: Pauses,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseMatrixResponse {
+    pub e_break: bool,
+    pub global: Pauses,
+    pub collections: Vec<CollectionPauses>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SalesResponse {
+    pub sales: Vec<Sale>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SaleResponse {
+    pub sale: Option<Sale>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TradeResponse {
     pub trade: Option<Trade>,
 }
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingResponse {
+    pub listing: Option<Listing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OffersResponse {
+    pub offers: Vec<Offer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OfferResponse {
+    pub offer: Option<Offer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionOffersResponse {
+    pub offers: Vec<CollectionOffer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionOfferResponse {
+    pub offer: Option<CollectionOffer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<Operator>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorResponse {
+    pub operator: Option<Operator>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionFeesResponse {
+    pub fees: FeeSchedule,
+    /// false when the global fees apply
+    pub is_override: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HolderDiscountResponse {
+    pub discount: FeeDiscount,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyPolicyResponse {
+    pub policy: RoyaltyPolicy,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub admin: Addr,
@@ -88,5 +562,9 @@ pub struct ConfigResponse {
     pub e_break: bool,
     pub(crate) buyer_fee: Decimal,
     pub(crate) seller_fee: Decimal,
-    pub listing_fee: Uint128
+    pub listing_fees: Vec<Coin>,
+    pub cw20_listing_fees: Vec<Cw20CoinVerified>,
+    pub fee_admin_pubkey: Option<Binary>,
+    pub pending_admin: Option<PendingTransfer>,
+    pub pending_fee_admin: Option<PendingTransfer>,
 }
\ No newline at end of file
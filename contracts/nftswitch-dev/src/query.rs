@@ -1,10 +1,25 @@
-use crate::msg::{ConfigResponse, TradeResponse, TradesResponse};
-use crate::state::{trades, CONFIG};
-use cosmwasm_std::{Addr, Deps, Order, StdResult};
+use crate::helpers::{collection_fees, has_role, holder_discount};
+use crate::msg::{
+    CollectionFeesResponse, CollectionOfferResponse, CollectionPauses, HasRoleResponse, PauseMatrixResponse, HolderDiscountResponse, RoleMembersResponse, ListingOffset,
+    OfferOffset, SaleResponse, SalesResponse, StatsResponse, TradeFilter, TradeOffset, CollectionOffersResponse, ConfigResponse, ListingResponse,
+    ListingsResponse, OfferResponse, OffersResponse, OperatorResponse, OperatorsResponse,
+    RoyaltyPolicyResponse, TradeResponse, TradesResponse, PendingCollectionFeeChangeResponse, PendingFeeChangeResponse,
+};
+use crate::state::{
+    collection_offer_key, collection_offers, listing_key, listings, offer_key, offers, sales,
+    trades, Role, TradeStats, COLLECTION_PAUSES, ROLES, BUYER_STATS, COLLECTION_STATS, GLOBAL_STATS, SELLER_STATS,
+    Trade, TradeKey, COLLECTION_FEES, CONFIG, OPERATORS, ROYALTY_POLICIES, TOKEN_RESERVATIONS,
+    FeeBounds, FEE_BOUNDS, PENDING_COLLECTION_FEE_CHANGES, PENDING_FEE_CHANGE,
+};
+use cosmwasm_std::{Addr, Api, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::{Bound, Map};
+use cw2::{get_contract_version, ContractVersion};
 
 // Query limits
 const DEFAULT_QUERY_LIMIT: u32 = 10;
 const MAX_QUERY_LIMIT: u32 = 30;
+// Most trades a single trade list query reads, matching or not
+const MAX_TRADE_SCAN: usize = 100;
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let cfg = CONFIG.load(deps.storage)?;
@@ -16,10 +31,18 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         buyer_fee: cfg.buyer_fee,
         seller_fee: cfg.seller_fee,
         e_break: cfg.e_break,
-        listing_fee: cfg.listing_fee
+        listing_fees: cfg.listing_fees,
+        cw20_listing_fees: cfg.cw20_listing_fees,
+        fee_admin_pubkey: cfg.fee_admin_pubkey,
+        pending_admin: cfg.pending_admin,
+        pending_fee_admin: cfg.pending_fee_admin,
     })
 }
 
+pub fn query_contract_version(deps: Deps) -> StdResult<ContractVersion> {
+    get_contract_version(deps.storage)
+}
+
 pub fn query_trade(
     deps: Deps,
     buyer: Addr,
@@ -31,50 +54,609 @@ pub fn query_trade(
     Ok(TradeResponse { trade })
 }
 
-// fn query_trades(
-//     deps: Deps
-// ) -> StdResult<TradesResponse> {
-//     let list: Vec<_> = trades()
-//         .idx.id
-//         .prefix(i32::from(1))
-//         .range(deps.storage, None, None, Order::Ascending)
-//         .collect::<StdResult<_>>().unwrap()?;
-//
-//     Ok(TradesResponse { trades: list })
-// }
+pub fn query_token_reservation(
+    deps: Deps,
+    nft_collection: Addr,
+    nft_id: String,
+) -> StdResult<TradeResponse> {
+    let trade = match TOKEN_RESERVATIONS.may_load(deps.storage, (nft_collection, nft_id))? {
+        Some(key) => trades().may_load(deps.storage, key)?,
+        None => None,
+    };
+
+    Ok(TradeResponse { trade })
+}
+
 pub fn query_trades_by_buyer(
     deps: Deps,
+    env: Env,
     buyer: Addr,
     limit: Option<u32>,
+    include_expired: Option<bool>,
+    start_after: Option<TradeOffset>,
 ) -> StdResult<TradesResponse> {
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let include_expired = include_expired.unwrap_or(false);
+    let start = trade_offset_bound(deps.api, start_after)?;
 
     let trades = trades()
         .idx
         .buyer
         .prefix(buyer)
-        .range(deps.storage, None, None, Order::Ascending)
-        .take(limit)
-        .map(|res| res.map(|(_, b)|b))
-        .collect::<StdResult<Vec<_>>>().unwrap();
+        .range(deps.storage, start, None, Order::Ascending);
 
-    Ok(TradesResponse { trades })
+    trades_page(trades, limit, |trade| include_expired || !is_expired_trade(trade, &env))
 }
 
 pub fn query_trades_by_seller(
     deps: Deps,
+    env: Env,
     seller: Addr,
     limit: Option<u32>,
+    include_expired: Option<bool>,
+    start_after: Option<TradeOffset>,
 ) -> StdResult<TradesResponse> {
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let include_expired = include_expired.unwrap_or(false);
+    let start = trade_offset_bound(deps.api, start_after)?;
+
     let trades = trades()
         .idx
         .seller
         .prefix(seller)
-        .range(deps.storage, None, None, Order::Ascending)
+        .range(deps.storage, start, None, Order::Ascending);
+
+    trades_page(trades, limit, |trade| include_expired || !is_expired_trade(trade, &env))
+}
+
+pub fn query_trades_by_collection(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    limit: Option<u32>,
+    include_expired: Option<bool>,
+    start_after: Option<TradeOffset>,
+    filter: Option<TradeFilter>,
+) -> StdResult<TradesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let include_expired = include_expired.unwrap_or(false);
+    let filter = filter.unwrap_or_default();
+    trade_filter_validate(&filter)?;
+    let start = trade_offset_bound(deps.api, start_after)?;
+
+    let trades = trades()
+        .idx
+        .collection
+        .prefix(collection)
+        .range(deps.storage, start, None, Order::Ascending);
+
+    trades_page(trades, limit, |trade| {
+        (include_expired || !is_expired_trade(trade, &env)) && trade_matches(trade, &filter)
+    })
+}
+
+pub fn query_all_trades(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+    include_expired: Option<bool>,
+    start_after: Option<TradeOffset>,
+    filter: Option<TradeFilter>,
+) -> StdResult<TradesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let include_expired = include_expired.unwrap_or(false);
+    let filter = filter.unwrap_or_default();
+    trade_filter_validate(&filter)?;
+    let start = trade_offset_bound(deps.api, start_after)?;
+
+    let trades = trades()
+        .range(deps.storage, start, None, Order::Ascending);
+
+    trades_page(trades, limit, |trade| {
+        (include_expired || !is_expired_trade(trade, &env)) && trade_matches(trade, &filter)
+    })
+}
+
+// price bounds only mean something within one denom
+fn trade_filter_validate(filter: &TradeFilter) -> StdResult<()> {
+    if (filter.min_price.is_some() || filter.max_price.is_some()) && filter.denom.is_none() {
+        return Err(StdError::generic_err("A price bound needs a denom"));
+    }
+    Ok(())
+}
+
+fn trade_matches(trade: &Trade, filter: &TradeFilter) -> bool {
+    let amount = trade.price.amount();
+
+    filter.confirmed.map_or(true, |confirmed| trade.is_confirmed_trade == confirmed)
+        && filter.denom.as_ref().map_or(true, |denom| &trade.price.denom() == denom)
+        && filter.min_price.map_or(true, |min| amount >= min)
+        && filter.max_price.map_or(true, |max| amount <= max)
+}
+
+pub fn trade_offset_bound<'a>(
+    api: &dyn Api,
+    offset: Option<TradeOffset>,
+) -> StdResult<Option<Bound<'a, TradeKey>>> {
+    offset
+        .map(|offset| {
+            Ok(Bound::exclusive((
+                api.addr_validate(&offset.buyer)?,
+                api.addr_validate(&offset.nft_collection)?,
+                offset.nft_id,
+            )))
+        })
+        .transpose()
+}
+
+// Read at most MAX_TRADE_SCAN trades and keep up to `limit` of those that pass `keep`. A full page
+// or a scan that hit the cap may have more trades after it, so it points to the last trade read.
+fn trades_page(
+    trades: impl Iterator<Item = StdResult<(TradeKey, Trade)>>,
+    limit: usize,
+    keep: impl Fn(&Trade) -> bool,
+) -> StdResult<TradesResponse> {
+    let mut page = vec![];
+    let mut last = None;
+    let mut scanned = 0;
+    for res in trades.take(MAX_TRADE_SCAN) {
+        let (key, trade) = res?;
+        scanned += 1;
+        if keep(&trade) {
+            page.push(trade);
+        }
+        last = Some(key);
+        if page.len() == limit {
+            break;
+        }
+    }
+
+    let next = match last {
+        Some((buyer, nft_collection, nft_id)) if page.len() == limit || scanned == MAX_TRADE_SCAN => {
+            Some(TradeOffset::new(buyer.to_string(), nft_collection.to_string(), nft_id))
+        }
+        _ => None,
+    };
+
+    Ok(TradesResponse { trades: page, next })
+}
+
+fn is_expired_trade(trade: &Trade, env: &Env) -> bool {
+    trade.expires_at.is_expired(&env.block)
+}
+
+pub fn query_listing(deps: Deps, nft_collection: Addr, nft_id: String) -> StdResult<ListingResponse> {
+    let listing = listings().may_load(deps.storage, listing_key(&nft_collection, nft_id))?;
+
+    Ok(ListingResponse { listing })
+}
+
+pub fn query_listings_by_collection(
+    deps: Deps,
+    collection: Addr,
+    limit: Option<u32>,
+    start_after: Option<ListingOffset>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = listing_offset_bound(deps.api, start_after)?;
+    let listings = listings()
+        .idx
+        .collection
+        .prefix(collection)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, l)| l))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListingsResponse { listings })
+}
+
+pub fn query_listings_by_seller(
+    deps: Deps,
+    seller: Addr,
+    limit: Option<u32>,
+    start_after: Option<ListingOffset>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = listing_offset_bound(deps.api, start_after)?;
+    let listings = listings()
+        .idx
+        .seller
+        .prefix(seller)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, l)| l))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListingsResponse { listings })
+}
+
+fn listing_offset_bound<'a>(
+    api: &dyn Api,
+    offset: Option<ListingOffset>,
+) -> StdResult<Option<Bound<'a, (Addr, String)>>> {
+    offset
+        .map(|offset| {
+            Ok(Bound::exclusive((
+                api.addr_validate(&offset.nft_collection)?,
+                offset.nft_id,
+            )))
+        })
+        .transpose()
+}
+
+pub fn query_offer(
+    deps: Deps,
+    nft_collection: Addr,
+    nft_id: String,
+    bidder: Addr,
+) -> StdResult<OfferResponse> {
+    let offer = offers().may_load(deps.storage, offer_key(&nft_collection, nft_id, &bidder))?;
+
+    Ok(OfferResponse { offer })
+}
+
+pub fn query_offers_by_token(
+    deps: Deps,
+    nft_collection: Addr,
+    nft_id: String,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = addr_bound(deps.api, start_after)?;
+    let offers = offers()
+        .prefix((nft_collection, nft_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersResponse { offers })
+}
+
+pub fn query_offers_by_bidder(
+    deps: Deps,
+    bidder: Addr,
+    limit: Option<u32>,
+    start_after: Option<OfferOffset>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = offer_offset_bound(deps.api, start_after)?;
+    let offers = offers()
+        .idx
+        .bidder
+        .prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersResponse { offers })
+}
+
+pub fn query_offers_by_collection(
+    deps: Deps,
+    collection: Addr,
+    limit: Option<u32>,
+    start_after: Option<OfferOffset>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = offer_offset_bound(deps.api, start_after)?;
+    let offers = offers()
+        .idx
+        .collection
+        .prefix(collection)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OffersResponse { offers })
+}
+
+fn offer_offset_bound<'a>(
+    api: &dyn Api,
+    offset: Option<OfferOffset>,
+) -> StdResult<Option<Bound<'a, (Addr, String, Addr)>>> {
+    offset
+        .map(|offset| {
+            Ok(Bound::exclusive((
+                api.addr_validate(&offset.nft_collection)?,
+                offset.nft_id,
+                api.addr_validate(&offset.bidder)?,
+            )))
+        })
+        .transpose()
+}
+
+fn addr_bound<'a>(api: &dyn Api, start_after: Option<String>) -> StdResult<Option<Bound<'a, Addr>>> {
+    start_after
+        .map(|addr| Ok(Bound::exclusive(api.addr_validate(&addr)?)))
+        .transpose()
+}
+
+pub fn query_collection_offer(
+    deps: Deps,
+    nft_collection: Addr,
+    bidder: Addr,
+) -> StdResult<CollectionOfferResponse> {
+    let offer =
+        collection_offers().may_load(deps.storage, collection_offer_key(&nft_collection, &bidder))?;
+
+    Ok(CollectionOfferResponse { offer })
+}
+
+pub fn query_collection_offers_by_collection(
+    deps: Deps,
+    collection: Addr,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<CollectionOffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = addr_bound(deps.api, start_after)?;
+    let offers = collection_offers()
+        .prefix(collection)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionOffersResponse { offers })
+}
+
+pub fn query_collection_offers_by_bidder(
+    deps: Deps,
+    bidder: Addr,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<CollectionOffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = match start_after {
+        Some(collection) => Some(Bound::exclusive(collection_offer_key(
+            &deps.api.addr_validate(&collection)?,
+            &bidder,
+        ))),
+        None => None,
+    };
+    let offers = collection_offers()
+        .idx
+        .bidder
+        .prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionOffersResponse { offers })
+}
+
+pub fn query_operator(deps: Deps, principal: Addr, operator: Addr) -> StdResult<OperatorResponse> {
+    let operator = OPERATORS.may_load(deps.storage, (principal, operator))?;
+
+    Ok(OperatorResponse { operator })
+}
+
+pub fn query_operators(
+    deps: Deps,
+    principal: Addr,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<OperatorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = addr_bound(deps.api, start_after)?;
+    let operators = OPERATORS
+        .prefix(principal)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, o)| o))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OperatorsResponse { operators })
+}
+
+pub fn query_fee_bounds(deps: Deps) -> StdResult<FeeBounds> {
+    Ok(FEE_BOUNDS.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_pending_fee_change(deps: Deps) -> StdResult<PendingFeeChangeResponse> {
+    Ok(PendingFeeChangeResponse {
+        change: PENDING_FEE_CHANGE.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_pending_collection_fee_change(
+    deps: Deps,
+    collection: Addr,
+) -> StdResult<PendingCollectionFeeChangeResponse> {
+    Ok(PendingCollectionFeeChangeResponse {
+        change: PENDING_COLLECTION_FEE_CHANGES.may_load(deps.storage, collection)?,
+    })
+}
+
+pub fn query_pause_matrix(
+    deps: Deps,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<PauseMatrixResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = addr_bound(deps.api, start_after)?;
+    let collections = COLLECTION_PAUSES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(collection, pauses)| CollectionPauses { collection, pauses }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PauseMatrixResponse {
+        e_break: cfg.e_break,
+        global: cfg.pauses,
+        collections,
+    })
+}
+
+pub fn query_role_members(
+    deps: Deps,
+    role: Role,
+    limit: Option<u32>,
+    start_after: Option<String>,
+) -> StdResult<RoleMembersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = addr_bound(deps.api, start_after)?;
+    let members = ROLES
+        .prefix(role.as_str().to_string())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RoleMembersResponse { members })
+}
+
+pub fn query_has_role(deps: Deps, role: Role, address: Addr) -> StdResult<HasRoleResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    Ok(HasRoleResponse {
+        has_role: has_role(deps, &cfg, role, &address)?,
+    })
+}
+
+pub fn query_royalty_policy(deps: Deps, collection: Addr) -> StdResult<RoyaltyPolicyResponse> {
+    let policy = ROYALTY_POLICIES
+        .may_load(deps.storage, collection)?
+        .unwrap_or_default();
+
+    Ok(RoyaltyPolicyResponse { policy })
+}
+
+pub fn query_collection_fees(deps: Deps, collection: Addr) -> StdResult<CollectionFeesResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let fees = collection_fees(deps, &cfg, &collection)?;
+    let is_override = COLLECTION_FEES.has(deps.storage, collection);
+
+    Ok(CollectionFeesResponse { fees, is_override })
+}
+
+pub fn query_holder_discount(deps: Deps, address: Addr) -> StdResult<HolderDiscountResponse> {
+    let discount =
+        holder_discount(deps, &address).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(HolderDiscountResponse { discount })
+}
+
+pub fn query_sale(deps: Deps, id: u64) -> StdResult<SaleResponse> {
+    let sale = sales().may_load(deps.storage, id)?;
+
+    Ok(SaleResponse { sale })
+}
+
+pub fn query_sales_by_collection(
+    deps: Deps,
+    collection: Addr,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<SalesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let sales = sales()
+        .idx
+        .collection
+        .prefix(collection)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SalesResponse { sales })
+}
+
+pub fn query_sales_by_token(
+    deps: Deps,
+    nft_collection: Addr,
+    nft_id: String,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<SalesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let sales = sales()
+        .idx
+        .token
+        .prefix((nft_collection, nft_id))
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
         .take(limit)
-        .map(|res| res.map(|(_, b)|b))
-        .collect::<StdResult<Vec<_>>>().unwrap();
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SalesResponse { sales })
+}
+
+pub fn query_sales_by_buyer(
+    deps: Deps,
+    buyer: Addr,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<SalesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let sales = sales()
+        .idx
+        .buyer
+        .prefix(buyer)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SalesResponse { sales })
+}
+
+pub fn query_sales_by_seller(
+    deps: Deps,
+    seller: Addr,
+    limit: Option<u32>,
+    start_after: Option<u64>,
+) -> StdResult<SalesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let sales = sales()
+        .idx
+        .seller
+        .prefix(seller)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SalesResponse { sales })
+}
+
+pub fn query_global_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let stats = GLOBAL_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(StatsResponse { stats })
+}
+
+pub fn query_collection_stats(deps: Deps, collection: Addr) -> StdResult<StatsResponse> {
+    stats_by_addr(deps, COLLECTION_STATS, collection)
+}
+
+pub fn query_seller_stats(deps: Deps, seller: Addr) -> StdResult<StatsResponse> {
+    stats_by_addr(deps, SELLER_STATS, seller)
+}
+
+pub fn query_buyer_stats(deps: Deps, buyer: Addr) -> StdResult<StatsResponse> {
+    stats_by_addr(deps, BUYER_STATS, buyer)
+}
+
+fn stats_by_addr(
+    deps: Deps,
+    map: Map<(Addr, String), TradeStats>,
+    addr: Addr,
+) -> StdResult<StatsResponse> {
+    let stats = map
+        .prefix(addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(_, s)| s))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(TradesResponse { trades })
+    Ok(StatsResponse { stats })
 }
@@ -1,18 +1,118 @@
+use std::fmt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Coin, Decimal, DepsMut, Env, MessageInfo, Uint128};
-use cw_storage_plus::{Index, IndexedMap, IndexList, Item, MultiIndex};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, DepsMut, Env, MessageInfo, Timestamp, Uint128};
+use cw20::Cw20CoinVerified;
+use cw_storage_plus::{Index, IndexedMap, IndexList, Item, Map, MultiIndex};
+use cw_utils::Expiration;
+
+/// Price of a trade, paid either in a native denom or in a CW20 token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TradePrice {
+    Native(Coin),
+    Cw20(Cw20CoinVerified),
+}
+
+impl TradePrice {
+    pub fn amount(&self) -> Uint128 {
+        match self {
+            TradePrice::Native(coin) => coin.amount,
+            TradePrice::Cw20(coin) => coin.amount,
+        }
+    }
+
+    /// Native denom, or the CW20 contract address
+    pub fn denom(&self) -> String {
+        match self {
+            TradePrice::Native(coin) => coin.denom.clone(),
+            TradePrice::Cw20(coin) => coin.address.to_string(),
+        }
+    }
+
+    /// Same asset, different amount
+    pub fn with_amount(&self, amount: Uint128) -> TradePrice {
+        match self {
+            TradePrice::Native(coin) => TradePrice::Native(Coin::new(amount.u128(), &coin.denom)),
+            TradePrice::Cw20(coin) => TradePrice::Cw20(Cw20CoinVerified {
+                address: coin.address.clone(),
+                amount,
+            }),
+        }
+    }
+}
+
+impl From<Coin> for TradePrice {
+    fn from(coin: Coin) -> Self {
+        TradePrice::Native(coin)
+    }
+}
+
+impl fmt::Display for TradePrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount(), self.denom())
+    }
+}
+
+/// A single token of a cw721 collection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftItem {
+    pub collection: Addr,
+    pub token_id: String,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Trade {
     pub seller: Addr,
     pub buyer: Addr,
-    pub price: Coin,
+    pub price: TradePrice,
     pub nft_collection: Addr,
     pub nft_id: String,
     pub is_confirmed_trade: bool,
     pub seller_fee: Decimal,
     pub buyer_fee: Decimal,
+    /// Extra NFTs the seller hands over along with `nft_collection`/`nft_id` (swap or bundle)
+    #[serde(default)]
+    pub offered_nfts: Vec<NftItem>,
+    /// NFTs the buyer must hand over to the seller in return (swap)
+    #[serde(default)]
+    pub wanted_nfts: Vec<NftItem>,
+    /// Amount of the price asset the seller adds to their side, held by the contract
+    #[serde(default)]
+    pub seller_coins: Uint128,
+    /// Block time or height after which the trade can no longer be confirmed or executed
+    #[serde(default)]
+    pub expires_at: Expiration,
+    /// The seller's NFTs are held by the contract until the trade is executed or dropped
+    #[serde(default)]
+    pub nft_in_escrow: bool,
+    /// Listing fee paid by the seller in the price asset, held by the contract until the trade ends
+    #[serde(default)]
+    pub listing_fee: Uint128,
+}
+
+impl Trade {
+    /// Every NFT the seller hands over
+    pub fn seller_nfts(&self) -> Vec<NftItem> {
+        let mut nfts = vec![NftItem {
+            collection: self.nft_collection.clone(),
+            token_id: self.nft_id.clone(),
+        }];
+        nfts.extend(self.offered_nfts.iter().cloned());
+        nfts
+    }
+
+    /// Collection of every NFT on either side
+    pub fn collections(&self) -> Vec<&Addr> {
+        let mut collections = vec![&self.nft_collection];
+        collections.extend(
+            self.offered_nfts
+                .iter()
+                .chain(self.wanted_nfts.iter())
+                .map(|nft| &nft.collection),
+        );
+        collections
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -22,8 +122,80 @@ pub struct Config {
     pub commission_addr: Addr,
     pub seller_fee: Decimal,
     pub buyer_fee: Decimal,
-    pub listing_fee: Uint128,
+    /// Accepted denoms, each paired with the listing fee charged in that denom
+    pub listing_fees: Vec<Coin>,
+    /// Accepted CW20 tokens, each paired with the listing fee charged in that token
+    pub cw20_listing_fees: Vec<Cw20CoinVerified>,
+    /// Halts creating, confirming and executing trades, cancellations stay open
     pub e_break: bool,
+    #[serde(default)]
+    pub pauses: Pauses,
+    /// secp256k1 public key of the fee admin, for fee confirmations signed off-chain
+    #[serde(default)]
+    pub fee_admin_pubkey: Option<Binary>,
+    /// Admin proposed by the current admin, waiting for them to accept
+    #[serde(default)]
+    pub pending_admin: Option<PendingTransfer>,
+    /// Fee admin proposed by the admin, waiting for them to accept
+    #[serde(default)]
+    pub pending_fee_admin: Option<PendingTransfer>,
+}
+
+/// Trading steps that can be paused on their own
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseAction {
+    Create,
+    Confirm,
+    Execute,
+    Cancel,
+}
+
+impl PauseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseAction::Create => "create",
+            PauseAction::Confirm => "confirm",
+            PauseAction::Execute => "execute",
+            PauseAction::Cancel => "cancel",
+        }
+    }
+}
+
+/// Paused trading steps, for the whole contract or a single collection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Pauses {
+    pub create: bool,
+    pub confirm: bool,
+    pub execute: bool,
+    pub cancel: bool,
+}
+
+impl Pauses {
+    pub fn is_paused(&self, action: PauseAction) -> bool {
+        match action {
+            PauseAction::Create => self.create,
+            PauseAction::Confirm => self.confirm,
+            PauseAction::Execute => self.execute,
+            PauseAction::Cancel => self.cancel,
+        }
+    }
+
+    pub fn set(&mut self, action: PauseAction, paused: bool) {
+        match action {
+            PauseAction::Create => self.create = paused,
+            PauseAction::Confirm => self.confirm = paused,
+            PauseAction::Execute => self.execute = paused,
+            PauseAction::Cancel => self.cancel = paused,
+        }
+    }
+}
+
+/// A role handed over once the proposed address accepts it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransfer {
+    pub new_address: Addr,
+    pub expires_at: Expiration,
 }
 
 /// (Buyer, Seller, NFT Contract, NFT ID)
@@ -35,6 +207,145 @@ pub fn trade_key(buyer: &Addr, nft_collection: &Addr, nft_id: String) -> TradeKe
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// An address allowed to create, cancel and execute trades for `principal`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Operator {
+    pub principal: Addr,
+    pub operator: Addr,
+    pub expires_at: Expiration,
+    /// Collections the operator may act on, all of them when empty
+    pub collections: Vec<Addr>,
+}
+
+/// (Principal, Operator)
+pub const OPERATORS: Map<(Addr, Addr), Operator> = Map::new("operators");
+
+/// (NFT Contract, NFT ID) -> trade holding the token, only one live trade may sell a token
+pub const TOKEN_RESERVATIONS: Map<(Addr, String), TradeKey> = Map::new("token_reservations");
+
+/// Fee rates applied to a collection's trades
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSchedule {
+    pub buyer_fee: Decimal,
+    pub seller_fee: Decimal,
+    /// Partnership collections trade at or below the global rates
+    pub partner: bool,
+}
+
+/// Admin managed fee overrides per collection, the global `Config` fees apply when unset
+pub const COLLECTION_FEES: Map<Addr, FeeSchedule> = Map::new("collection_fees");
+
+/// Share of the buyer and seller fee waived for holders of a membership collection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct FeeDiscount {
+    pub buyer_discount: Decimal,
+    pub seller_discount: Decimal,
+}
+
+/// Discount table keyed by membership collection
+pub const HOLDER_DISCOUNTS: Map<Addr, FeeDiscount> = Map::new("holder_discounts");
+
+/// Every settlement queries each membership collection, so their number is capped
+pub const MAX_HOLDER_DISCOUNTS: u32 = 5;
+
+/// How trades in a collection treat cw2981 royalties
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoyaltyPolicy {
+    /// Royalties are paid, and trades fail when the collection can not report them
+    Enforce,
+    /// Royalties are paid when the collection reports them
+    Honor,
+    /// Royalties are never paid
+    Ignore,
+}
+
+impl Default for RoyaltyPolicy {
+    fn default() -> Self {
+        RoyaltyPolicy::Honor
+    }
+}
+
+/// Privileged duties the admin hands out, the admin itself holds every role
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Flips the emergency break and pauses or resumes trading steps through `SetPause`
+    Pauser,
+    /// Sets global and per-collection fees and holder discounts
+    FeeSetter,
+    /// Confirms trade and listing fees, also held by the fee admin
+    TradeConfirmer,
+    /// Sets collection royalty policies
+    CollectionCurator,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Pauser => "pauser",
+            Role::FeeSetter => "fee_setter",
+            Role::TradeConfirmer => "trade_confirmer",
+            Role::CollectionCurator => "collection_curator",
+        }
+    }
+}
+
+/// Highest fee rates that can be set, and how long fee increases wait before they apply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeBounds {
+    pub max_buyer_fee: Decimal,
+    pub max_seller_fee: Decimal,
+    /// Seconds
+    pub increase_delay: u64,
+}
+
+impl Default for FeeBounds {
+    fn default() -> Self {
+        FeeBounds {
+            max_buyer_fee: Decimal::percent(10),
+            max_seller_fee: Decimal::percent(10),
+            increase_delay: 86400,
+        }
+    }
+}
+
+/// `FeeBounds::default()` when unset
+pub const FEE_BOUNDS: Item<FeeBounds> = Item::new("fee_bounds");
+
+/// Global fee increase waiting out the timelock, `None` leaves that fee as it is
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFeeChange {
+    pub buyer_fee: Option<Decimal>,
+    pub seller_fee: Option<Decimal>,
+    pub effective_at: Timestamp,
+}
+
+pub const PENDING_FEE_CHANGE: Item<PendingFeeChange> = Item::new("pending_fee_change");
+
+/// Collection fee increase waiting out the timelock, `None` fees return the collection to the
+/// global fees
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCollectionFeeChange {
+    pub fees: Option<FeeSchedule>,
+    pub effective_at: Timestamp,
+}
+
+pub const PENDING_COLLECTION_FEE_CHANGES: Map<Addr, PendingCollectionFeeChange> =
+    Map::new("pending_collection_fee_changes");
+
+/// Pauses per collection, only stored while at least one step is paused
+pub const COLLECTION_PAUSES: Map<Addr, Pauses> = Map::new("collection_pauses");
+
+/// (Role, Member)
+pub const ROLES: Map<(String, Addr), bool> = Map::new("roles");
+
+/// Royalty policy per collection, `RoyaltyPolicy::Honor` when unset
+pub const ROYALTY_POLICIES: Map<Addr, RoyaltyPolicy> = Map::new("royalty_policies");
+
+/// Nonces of signed fee confirmations already spent
+pub const FEE_CONFIRMATION_NONCES: Map<u64, bool> = Map::new("fee_confirmation_nonces");
+
 pub struct ExecuteEnv<'a> {
     pub(crate) deps: DepsMut<'a>,
     pub(crate) env: Env,
@@ -58,17 +369,390 @@ impl<'a> IndexList<Trade> for TradeIndices<'a> {
 pub fn trades<'a>() -> IndexedMap<'a, TradeKey, Trade, TradeIndices<'a>> {
     let indexes = TradeIndices {
         collection: MultiIndex::new(
-            |d: &Trade| (d.nft_collection.clone()),
+            |d: &Trade| d.nft_collection.clone(),
             "trades",
             "trades__nft_collection"),
         buyer: MultiIndex::new(
-            |d: &Trade| (d.buyer.clone()),
+            |d: &Trade| d.buyer.clone(),
             "trades",
             "trades__buyer"),
         seller: MultiIndex::new(
-            |d: &Trade| (d.seller.clone()),
+            |d: &Trade| d.seller.clone(),
             "trades",
             "trades__seller"),
     };
     IndexedMap::new("trades", indexes)
 }
+
+/// Config as stored by 1.0.0, with a single listing fee in uluna
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub admin: Addr,
+    pub fee_admin: Addr,
+    pub commission_addr: Addr,
+    pub seller_fee: Decimal,
+    pub buyer_fee: Decimal,
+    pub listing_fee: Uint128,
+    pub e_break: bool,
+}
+
+/// Trade as stored by 1.0.0, priced in a native coin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyTrade {
+    pub seller: Addr,
+    pub buyer: Addr,
+    pub price: Coin,
+    pub nft_collection: Addr,
+    pub nft_id: String,
+    pub is_confirmed_trade: bool,
+    pub seller_fee: Decimal,
+    pub buyer_fee: Decimal,
+}
+
+impl From<LegacyTrade> for Trade {
+    fn from(trade: LegacyTrade) -> Self {
+        Trade {
+            seller: trade.seller,
+            buyer: trade.buyer,
+            price: TradePrice::Native(trade.price),
+            nft_collection: trade.nft_collection,
+            nft_id: trade.nft_id,
+            is_confirmed_trade: trade.is_confirmed_trade,
+            seller_fee: trade.seller_fee,
+            buyer_fee: trade.buyer_fee,
+            offered_nfts: vec![],
+            wanted_nfts: vec![],
+            seller_coins: Uint128::zero(),
+            expires_at: Expiration::Never {},
+            nft_in_escrow: false,
+            // 1.0.0 forwarded the listing fee when the trade was created
+            listing_fee: Uint128::zero(),
+        }
+    }
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// Primary entries of `trades()` as 1.0.0 wrote them, the index layout is unchanged
+pub const LEGACY_TRADES: Map<TradeKey, LegacyTrade> = Map::new("trades");
+
+/// An open listing, executable by any address at the listed price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub seller: Addr,
+    pub price: TradePrice,
+    pub nft_collection: Addr,
+    pub nft_id: String,
+    pub is_confirmed_listing: bool,
+    pub seller_fee: Decimal,
+    pub buyer_fee: Decimal,
+    /// Listing fee paid by the seller in the price asset, held by the contract until the listing ends
+    #[serde(default)]
+    pub listing_fee: Uint128,
+}
+
+impl Listing {
+    /// The trade settled when `buyer` takes the listing
+    pub fn into_trade(self, buyer: Addr) -> Trade {
+        Trade {
+            seller: self.seller,
+            buyer,
+            price: self.price,
+            nft_collection: self.nft_collection,
+            nft_id: self.nft_id,
+            is_confirmed_trade: self.is_confirmed_listing,
+            seller_fee: self.seller_fee,
+            buyer_fee: self.buyer_fee,
+            offered_nfts: vec![],
+            wanted_nfts: vec![],
+            seller_coins: Uint128::zero(),
+            expires_at: Expiration::Never {},
+            nft_in_escrow: false,
+            listing_fee: self.listing_fee,
+        }
+    }
+}
+
+/// (NFT Contract, NFT ID)
+pub type ListingKey = (Addr, String);
+
+pub fn listing_key(nft_collection: &Addr, nft_id: String) -> ListingKey {
+    (nft_collection.clone(), nft_id)
+}
+
+/// Defines indices for accessing open listings
+pub struct ListingIndices<'a> {
+    pub collection: MultiIndex<'a, Addr, Listing, ListingKey>,
+    pub seller: MultiIndex<'a, Addr, Listing, ListingKey>,
+}
+
+impl<'a> IndexList<Listing> for ListingIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item=&'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.collection, &self.seller];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn listings<'a>() -> IndexedMap<'a, ListingKey, Listing, ListingIndices<'a>> {
+    let indexes = ListingIndices {
+        collection: MultiIndex::new(
+            |d: &Listing| d.nft_collection.clone(),
+            "listings",
+            "listings__nft_collection"),
+        seller: MultiIndex::new(
+            |d: &Listing| d.seller.clone(),
+            "listings",
+            "listings__seller"),
+    };
+    IndexedMap::new("listings", indexes)
+}
+
+/// A bid on a token the bidder does not own, with the funds held by the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Offer {
+    pub bidder: Addr,
+    pub price: TradePrice,
+    pub nft_collection: Addr,
+    pub nft_id: String,
+    pub seller_fee: Decimal,
+    pub buyer_fee: Decimal,
+}
+
+impl Offer {
+    /// Amount held by the contract, the price plus the bidder's fee
+    pub fn escrow(&self) -> Uint128 {
+        self.price.amount() + self.buyer_fee * self.price.amount()
+    }
+
+    /// The trade settled when `seller` accepts the offer
+    pub fn into_trade(self, seller: Addr) -> Trade {
+        Trade {
+            seller,
+            buyer: self.bidder,
+            price: self.price,
+            nft_collection: self.nft_collection,
+            nft_id: self.nft_id,
+            is_confirmed_trade: true,
+            seller_fee: self.seller_fee,
+            buyer_fee: self.buyer_fee,
+            offered_nfts: vec![],
+            wanted_nfts: vec![],
+            seller_coins: Uint128::zero(),
+            expires_at: Expiration::Never {},
+            nft_in_escrow: false,
+            listing_fee: Uint128::zero(),
+        }
+    }
+}
+
+/// (NFT Contract, NFT ID, Bidder)
+pub type OfferKey = (Addr, String, Addr);
+
+pub fn offer_key(nft_collection: &Addr, nft_id: String, bidder: &Addr) -> OfferKey {
+    (nft_collection.clone(), nft_id, bidder.clone())
+}
+
+/// Defines indices for accessing offers
+pub struct OfferIndices<'a> {
+    pub collection: MultiIndex<'a, Addr, Offer, OfferKey>,
+    pub bidder: MultiIndex<'a, Addr, Offer, OfferKey>,
+}
+
+impl<'a> IndexList<Offer> for OfferIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item=&'_ dyn Index<Offer>> + '_> {
+        let v: Vec<&dyn Index<Offer>> = vec![&self.collection, &self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn offers<'a>() -> IndexedMap<'a, OfferKey, Offer, OfferIndices<'a>> {
+    let indexes = OfferIndices {
+        collection: MultiIndex::new(
+            |d: &Offer| d.nft_collection.clone(),
+            "offers",
+            "offers__nft_collection"),
+        bidder: MultiIndex::new(
+            |d: &Offer| d.bidder.clone(),
+            "offers",
+            "offers__bidder"),
+    };
+    IndexedMap::new("offers", indexes)
+}
+
+/// A bid on any token of a collection, filled by up to `quantity` tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionOffer {
+    pub bidder: Addr,
+    /// Price paid for each token
+    pub price: TradePrice,
+    pub nft_collection: Addr,
+    /// Tokens still wanted, the offer is removed once it reaches zero
+    pub quantity: u32,
+    pub seller_fee: Decimal,
+    pub buyer_fee: Decimal,
+}
+
+impl CollectionOffer {
+    /// Amount held by the contract for a single token
+    pub fn escrow_per_token(&self) -> Uint128 {
+        self.price.amount() + self.buyer_fee * self.price.amount()
+    }
+
+    /// Amount held by the contract for all tokens still wanted
+    pub fn escrow(&self) -> Uint128 {
+        self.escrow_per_token() * Uint128::from(self.quantity)
+    }
+
+    /// The trade settled when `seller` fills the offer with `nft_id`
+    pub fn to_trade(&self, seller: Addr, nft_id: String) -> Trade {
+        Trade {
+            seller,
+            buyer: self.bidder.clone(),
+            price: self.price.clone(),
+            nft_collection: self.nft_collection.clone(),
+            nft_id,
+            is_confirmed_trade: true,
+            seller_fee: self.seller_fee,
+            buyer_fee: self.buyer_fee,
+            offered_nfts: vec![],
+            wanted_nfts: vec![],
+            seller_coins: Uint128::zero(),
+            expires_at: Expiration::Never {},
+            nft_in_escrow: false,
+            listing_fee: Uint128::zero(),
+        }
+    }
+}
+
+/// (NFT Contract, Bidder)
+pub type CollectionOfferKey = (Addr, Addr);
+
+pub fn collection_offer_key(nft_collection: &Addr, bidder: &Addr) -> CollectionOfferKey {
+    (nft_collection.clone(), bidder.clone())
+}
+
+/// Defines indices for accessing collection offers
+pub struct CollectionOfferIndices<'a> {
+    pub bidder: MultiIndex<'a, Addr, CollectionOffer, CollectionOfferKey>,
+}
+
+impl<'a> IndexList<CollectionOffer> for CollectionOfferIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item=&'_ dyn Index<CollectionOffer>> + '_> {
+        let v: Vec<&dyn Index<CollectionOffer>> = vec![&self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn collection_offers<'a>(
+) -> IndexedMap<'a, CollectionOfferKey, CollectionOffer, CollectionOfferIndices<'a>> {
+    let indexes = CollectionOfferIndices {
+        bidder: MultiIndex::new(
+            |d: &CollectionOffer| d.bidder.clone(),
+            "collection_offers",
+            "collection_offers__bidder"),
+    };
+    IndexedMap::new("collection_offers", indexes)
+}
+
+/// A settled trade, kept as the on-chain sales ledger
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sale {
+    /// Increases by one with every sale, starting at 1
+    pub id: u64,
+    pub seller: Addr,
+    pub buyer: Addr,
+    pub nft_collection: Addr,
+    pub nft_id: String,
+    pub price: TradePrice,
+    /// Fee amounts charged in the price asset
+    pub buyer_fee: Uint128,
+    pub seller_fee: Uint128,
+    pub royalty: Uint128,
+    pub timestamp: Timestamp,
+    /// Sale of the priced token when this one moved along with it in a bundle or swap. Such
+    /// entries carry a zero price and no fees
+    pub part_of: Option<u64>,
+}
+
+/// Id of the last recorded sale
+pub const SALE_COUNT: Item<u64> = Item::new("sale_count");
+
+/// Defines indices for accessing sales
+pub struct SaleIndices<'a> {
+    pub collection: MultiIndex<'a, Addr, Sale, u64>,
+    pub token: MultiIndex<'a, (Addr, String), Sale, u64>,
+    pub seller: MultiIndex<'a, Addr, Sale, u64>,
+    pub buyer: MultiIndex<'a, Addr, Sale, u64>,
+}
+
+impl<'a> IndexList<Sale> for SaleIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item=&'_ dyn Index<Sale>> + '_> {
+        let v: Vec<&dyn Index<Sale>> =
+            vec![&self.collection, &self.token, &self.seller, &self.buyer];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn sales<'a>() -> IndexedMap<'a, u64, Sale, SaleIndices<'a>> {
+    let indexes = SaleIndices {
+        collection: MultiIndex::new(
+            |d: &Sale| d.nft_collection.clone(),
+            "sales",
+            "sales__nft_collection"),
+        token: MultiIndex::new(
+            |d: &Sale| (d.nft_collection.clone(), d.nft_id.clone()),
+            "sales",
+            "sales__token"),
+        seller: MultiIndex::new(
+            |d: &Sale| d.seller.clone(),
+            "sales",
+            "sales__seller"),
+        buyer: MultiIndex::new(
+            |d: &Sale| d.buyer.clone(),
+            "sales",
+            "sales__buyer"),
+    };
+    IndexedMap::new("sales", indexes)
+}
+
+/// Running aggregates of executed trades in one denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TradeStats {
+    pub denom: String,
+    pub trade_count: u64,
+    pub volume: Uint128,
+    pub last_price: Uint128,
+    pub high_price: Uint128,
+    pub low_price: Uint128,
+}
+
+impl TradeStats {
+    pub fn new(denom: String) -> Self {
+        TradeStats {
+            denom,
+            trade_count: 0,
+            volume: Uint128::zero(),
+            last_price: Uint128::zero(),
+            high_price: Uint128::zero(),
+            low_price: Uint128::zero(),
+        }
+    }
+
+    /// Add one sale at `price`
+    pub fn record(&mut self, price: Uint128) {
+        self.low_price = if self.trade_count == 0 { price } else { self.low_price.min(price) };
+        self.high_price = self.high_price.max(price);
+        self.last_price = price;
+        self.volume += price;
+        self.trade_count += 1;
+    }
+}
+
+/// Stats keyed by denom
+pub const GLOBAL_STATS: Map<String, TradeStats> = Map::new("global_stats");
+/// Stats keyed by (collection, denom)
+pub const COLLECTION_STATS: Map<(Addr, String), TradeStats> = Map::new("collection_stats");
+/// Stats keyed by (seller, denom)
+pub const SELLER_STATS: Map<(Addr, String), TradeStats> = Map::new("seller_stats");
+/// Stats keyed by (buyer, denom)
+pub const BUYER_STATS: Map<(Addr, String), TradeStats> = Map::new("buyer_stats");
@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct DisburseReward {
-    pub amount: Uint128,
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    DisburseReward {
+        amount: Uint128,
+        denom: String,
+    },
 }